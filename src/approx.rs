@@ -0,0 +1,132 @@
+use crate::{lower_bound_index, upper_bound_index};
+use std::cmp::Ordering;
+use std::ops::Range;
+
+/// How close two floats must be to count as equal in [`ApproxBound`] queries.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tolerance {
+    /// `|a - b| <= eps`
+    Absolute(f64),
+    /// `|a - b| <= eps * max(|a|, |b|)`
+    Relative(f64),
+    /// `a` and `b` are at most this many representable values apart.
+    Ulps(u64),
+}
+
+/// A bare number is an absolute tolerance.
+impl From<f64> for Tolerance {
+    fn from(eps: f64) -> Self {
+        Tolerance::Absolute(eps)
+    }
+}
+
+/// Bounds over sorted float slices that treat values within a [`Tolerance`] of `x` as equal
+/// to it; assume the slice is sorted and free of NaN (or has NaN only at the end).
+///
+/// `lower_bound_approx` finds the first element not definitely less than `x`, that is neither
+/// less than `x` nor close to it, and `upper_bound_approx` the first element definitely greater.
+/// Every tolerance makes "close to `x`" a contiguous interval, so both are plain binary searches
+/// and `equal_range_approx` is the range of elements close to `x`.
+/// ```
+/// # use bound_stl::{ApproxBound, Tolerance};
+/// let v = [0.1, 0.2, 0.30000000000000004, 0.4];
+/// assert_eq!(v.lower_bound_approx(&0.3, 1e-9), Ok(2));
+/// assert_eq!(v.equal_range_approx(&0.3, 1e-9), 2..3);
+/// assert_eq!(v.equal_range_approx(&0.3, Tolerance::Ulps(1)), 2..3);
+/// assert_eq!(v.equal_range_approx(&0.3, Tolerance::Relative(0.5)), 1..4);
+/// assert_eq!(v.upper_bound_approx(&0.4, 1e-9), Err(4));
+/// ```
+pub trait ApproxBound {
+    type Item;
+
+    fn lower_bound_approx<E: Into<Tolerance>>(&self, x: &Self::Item, tol: E) -> Result<usize, usize>;
+
+    fn upper_bound_approx<E: Into<Tolerance>>(&self, x: &Self::Item, tol: E) -> Result<usize, usize>;
+
+    fn equal_range_approx<E: Into<Tolerance>>(&self, x: &Self::Item, tol: E) -> Range<usize>;
+}
+
+macro_rules! impl_approx_bound {
+    ($t:ty, $bits:ty) => {
+        impl ApproxBound for [$t] {
+            type Item = $t;
+
+            fn lower_bound_approx<E: Into<Tolerance>>(&self, x: &$t, tol: E) -> Result<usize, usize> {
+                let tol = tol.into();
+                lower_bound_index(self.len(), |i| {
+                    approx_cmp(self[i] as f64, *x as f64, self[i].ordered(), x.ordered(), tol)
+                })
+            }
+
+            fn upper_bound_approx<E: Into<Tolerance>>(&self, x: &$t, tol: E) -> Result<usize, usize> {
+                let tol = tol.into();
+                upper_bound_index(self.len(), |i| {
+                    approx_cmp(self[i] as f64, *x as f64, self[i].ordered(), x.ordered(), tol)
+                })
+            }
+
+            fn equal_range_approx<E: Into<Tolerance>>(&self, x: &$t, tol: E) -> Range<usize> {
+                let tol = tol.into();
+                let lo = self.lower_bound_approx(x, tol).unwrap_or_else(|e| e);
+                let hi = self.upper_bound_approx(x, tol).unwrap_or_else(|e| e);
+                lo..hi
+            }
+        }
+
+        impl ApproxOrdered for $t {
+            fn ordered(self) -> i64 {
+                let b = self.to_bits() as $bits;
+                (if b < 0 { b ^ <$bits>::MAX } else { b }) as i64
+            }
+        }
+    };
+}
+
+trait ApproxOrdered {
+    /// The float's position in the sequence of representable values.
+    fn ordered(self) -> i64;
+}
+
+impl_approx_bound!(f32, i32);
+impl_approx_bound!(f64, i64);
+
+/// Compares `a` against the target `x`; `Equal` when they are close, NaN compares greater.
+fn approx_cmp(a: f64, x: f64, a_ord: i64, x_ord: i64, tol: Tolerance) -> Ordering {
+    let close = match tol {
+        Tolerance::Absolute(eps) => (a - x).abs() <= eps,
+        Tolerance::Relative(eps) => (a - x).abs() <= eps * a.abs().max(x.abs()),
+        Tolerance::Ulps(n) => a_ord.abs_diff(x_ord) <= n,
+    };
+    if close {
+        Ordering::Equal
+    } else {
+        a.partial_cmp(&x).unwrap_or(Ordering::Greater)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::{ApproxBound, Tolerance};
+
+    #[test]
+    fn test_approx_bound() {
+        let v = [-1.0, -1e-12, 0.0, 1e-12, 0.5, 1.0, 1.0 + 1e-12, 2.0];
+        assert_eq!(v.equal_range_approx(&0.0, 1e-9), 1..4);
+        assert_eq!(v.equal_range_approx(&1.0, 1e-9), 5..7);
+        assert_eq!(v.equal_range_approx(&1.0, 0.0), 5..6);
+        assert_eq!(v.equal_range_approx(&0.75, 1e-9), 5..5);
+        assert_eq!(v.lower_bound_approx(&-5.0, 1e-9), Ok(0));
+        assert_eq!(v.lower_bound_approx(&5.0, 1e-9), Err(8));
+        assert_eq!(v.equal_range_approx(&1.5, Tolerance::Relative(0.4)), 5..8);
+
+        let next = f64::from_bits(1.0f64.to_bits() + 2);
+        let w = [1.0, next, 3.0];
+        assert_eq!(w.equal_range_approx(&1.0, Tolerance::Ulps(1)), 0..1);
+        assert_eq!(w.equal_range_approx(&1.0, Tolerance::Ulps(2)), 0..2);
+        assert_eq!([-0.0f64, 0.0].equal_range_approx(&0.0, Tolerance::Ulps(1)), 0..2);
+
+        let f = [0.1f32, 0.2, 0.3];
+        assert_eq!(f.equal_range_approx(&0.2, 1e-6), 1..2);
+        assert_eq!([1.0, f64::NAN].upper_bound_approx(&1.0, 0.0), Ok(1));
+    }
+}
@@ -0,0 +1,154 @@
+use crate::{lower_bound_index, upper_bound_index, LowerBound, UpperBound};
+use std::cmp::Ordering;
+
+/// A sorted permutation over an unsorted slice: ordered lookups without moving the data.
+///
+/// Bound queries return ranks in key order; [`original_index`](Self::original_index) and
+/// [`indices`](Self::indices) turn ranks back into positions in the backing slice, which
+/// other code may keep referring to. The sort is stable, so equal elements keep their
+/// original relative order. An index built with [`new_by_key`](Self::new_by_key) should be
+/// queried with the `_by_key` methods using the same key.
+/// ```
+/// # use bound_stl::{ArgsortIndex, LowerBound, UpperBound};
+/// let prices = [30, 10, 50, 20, 10];
+/// let idx = ArgsortIndex::new(&prices);
+/// assert_eq!(idx.indices(..), &[1, 4, 3, 0, 2]);
+/// let (lo, hi) = (idx.lower_bound(&15).unwrap(), idx.upper_bound(&30).unwrap());
+/// assert_eq!(idx.indices(lo..hi), &[3, 0]); // positions of 20 and 30 in `prices`
+/// ```
+#[derive(Debug, Clone)]
+pub struct ArgsortIndex<'a, T> {
+    data: &'a [T],
+    order: Vec<usize>,
+}
+
+impl<'a, T> ArgsortIndex<'a, T> {
+    pub fn new(data: &'a [T]) -> Self
+    where
+        T: Ord,
+    {
+        Self::new_by(data, T::cmp)
+    }
+
+    pub fn new_by<F>(data: &'a [T], mut compare: F) -> Self
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let mut order = (0..data.len()).collect::<Vec<_>>();
+        order.sort_by(|&i, &j| compare(&data[i], &data[j]));
+        ArgsortIndex { data, order }
+    }
+
+    pub fn new_by_key<K, F>(data: &'a [T], mut f: F) -> Self
+    where
+        F: FnMut(&T) -> K,
+        K: Ord,
+    {
+        Self::new_by(data, |a, b| f(a).cmp(&f(b)))
+    }
+
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    /// The backing slice.
+    pub fn data(&self) -> &'a [T] {
+        self.data
+    }
+
+    /// Position in the backing slice of the element at rank `rank`.
+    pub fn original_index(&self, rank: usize) -> usize {
+        self.order[rank]
+    }
+
+    /// Positions in the backing slice of the elements at the given ranks.
+    pub fn indices<R: std::slice::SliceIndex<[usize], Output = [usize]>>(&self, ranks: R) -> &[usize] {
+        &self.order[ranks]
+    }
+
+    /// The element at rank `rank`, with its position in the backing slice.
+    pub fn get(&self, rank: usize) -> Option<(usize, &'a T)> {
+        self.order.get(rank).map(|&i| (i, &self.data[i]))
+    }
+
+    /// `(position, element)` pairs in key order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (usize, &'a T)> + ExactSizeIterator + '_ {
+        let data = self.data;
+        self.order.iter().map(move |&i| (i, &data[i]))
+    }
+}
+
+impl<'a, T: Ord> LowerBound for ArgsortIndex<'a, T> {
+    type Item = T;
+    fn lower_bound(&self, x: &Self::Item) -> Result<usize, usize> {
+        self.lower_bound_by(|y| y.cmp(x))
+    }
+
+    fn lower_bound_by<'b, F>(&'b self, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'b Self::Item) -> Ordering,
+    {
+        lower_bound_index(self.len(), |r| f(&self.data[self.order[r]]))
+    }
+
+    fn lower_bound_by_key<'b, K, F>(&'b self, k: &K, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'b Self::Item) -> K,
+        K: Ord,
+    {
+        self.lower_bound_by(|y| f(y).cmp(k))
+    }
+}
+
+impl<'a, T: Ord> UpperBound for ArgsortIndex<'a, T> {
+    type Item = T;
+    fn upper_bound(&self, x: &Self::Item) -> Result<usize, usize> {
+        self.upper_bound_by(|y| y.cmp(x))
+    }
+
+    fn upper_bound_by<'b, F>(&'b self, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'b Self::Item) -> Ordering,
+    {
+        upper_bound_index(self.len(), |r| f(&self.data[self.order[r]]))
+    }
+
+    fn upper_bound_by_key<'b, K, F>(&'b self, k: &K, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'b Self::Item) -> K,
+        K: Ord,
+    {
+        self.upper_bound_by(|y| f(y).cmp(k))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::{ArgsortIndex, LowerBound, UpperBound};
+
+    #[test]
+    fn test_argsort_index() {
+        let data = [7, 3, 9, 3, 1, 8];
+        let idx = ArgsortIndex::new(&data);
+        assert_eq!(idx.indices(..), &[4, 1, 3, 0, 5, 2]);
+        let mut sorted = data.to_vec();
+        sorted.sort();
+        for x in 0..11 {
+            assert_eq!(idx.lower_bound(&x), sorted.lower_bound(&x));
+            assert_eq!(idx.upper_bound(&x), sorted.upper_bound(&x));
+        }
+        assert_eq!(idx.get(1), Some((1, &3)));
+        assert_eq!(idx.original_index(5), 2);
+        assert!(idx.iter().map(|(_, v)| v).eq(sorted.iter()));
+
+        let names = ["carol", "al", "bob"];
+        let by_len = ArgsortIndex::new_by_key(&names, |s| s.len());
+        assert_eq!(by_len.indices(..), &[1, 2, 0]);
+        assert_eq!(by_len.lower_bound_by_key(&3, |s| s.len()), Ok(1));
+        assert_eq!(by_len.upper_bound_by_key(&5, |s| s.len()), Err(3));
+    }
+}
@@ -0,0 +1,222 @@
+use crate::{lower_bound_index, upper_bound_index};
+use arrow_array::ArrayAccessor;
+use std::cmp::Ordering;
+use std::ops::Range;
+
+/// The order of the values of an Arrow array, as an array sort kernel leaves them.
+///
+/// Integers compare as usual, floats by `total_cmp` like Arrow's own kernels, and string
+/// and binary values lexicographically.
+pub trait ArrowOrd {
+    fn arrow_cmp(&self, other: &Self) -> Ordering;
+}
+
+macro_rules! impl_arrow_ord {
+    ($cmp:ident: $($t:ty),*) => {$(
+        impl ArrowOrd for $t {
+            fn arrow_cmp(&self, other: &Self) -> Ordering {
+                self.$cmp(other)
+            }
+        }
+    )*};
+}
+
+impl_arrow_ord!(cmp: i8, i16, i32, i64, i128, u8, u16, u32, u64, &str, &[u8]);
+impl_arrow_ord!(total_cmp: f32, f64);
+
+/// Where the nulls of a sorted array are; Arrow sorts them first by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NullOrder {
+    #[default]
+    NullsFirst,
+    NullsLast,
+}
+
+/// Bound queries over a sorted Arrow array, in place.
+///
+/// `A` is any typed accessor: `&PrimitiveArray<T>`, `&StringArray`, `&BinaryArray`, or a
+/// `TypedDictionaryArray` from `DictionaryArray::downcast_dict`, whose keys are sorted by the
+/// values they refer to. The nulls must form one block at the end given by [`NullOrder`];
+/// their slots are never read. Positions are indices into the whole array, and bounds follow
+/// the usual `Result` convention, searching only the valid values: a bound past the last
+/// valid value is an `Err`, also when nulls follow it.
+/// ```
+/// # use bound_stl::{NullOrder, SortedArrow};
+/// # use arrow_array::{DictionaryArray, Int32Array, StringArray, types::Int32Type};
+/// let ints = Int32Array::from(vec![None, Some(1), Some(3), Some(3), Some(8)]);
+/// let sorted = SortedArrow::new(&ints, NullOrder::NullsFirst);
+/// assert_eq!(sorted.lower_bound(&3), Ok(2));
+/// assert_eq!(sorted.upper_bound(&3), Ok(4));
+/// assert_eq!(sorted.lower_bound(&0), Ok(1));
+/// assert_eq!(sorted.digitize(&[0, 3, 9], false), [0, 3, 4]);
+///
+/// let names = StringArray::from(vec![Some("ann"), Some("bob"), None]);
+/// let sorted = SortedArrow::new(&names, NullOrder::NullsLast);
+/// assert_eq!(sorted.lookup(["bob", "cid"]), [Some(1), None]);
+/// assert_eq!(sorted.lower_bound(&"zed"), Err(2));
+///
+/// let dict = DictionaryArray::<Int32Type>::from_iter(["x", "y", "y", "z"]);
+/// let typed = dict.downcast_dict::<StringArray>().unwrap();
+/// let sorted = SortedArrow::new(typed, NullOrder::NullsFirst);
+/// assert_eq!(sorted.equal_range(&"y"), 1..3);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SortedArrow<A> {
+    array: A,
+    valid: Range<usize>,
+}
+
+impl<A> SortedArrow<A>
+where
+    A: ArrayAccessor,
+    A::Item: ArrowOrd,
+{
+    pub fn new(array: A, nulls: NullOrder) -> Self {
+        let (len, null_count) = (array.len(), array.null_count());
+        let valid = match nulls {
+            NullOrder::NullsFirst => null_count..len,
+            NullOrder::NullsLast => 0..len - null_count,
+        };
+        debug_assert!(valid.clone().all(|i| array.is_valid(i)), "nulls are not in one block");
+        SortedArrow { array, valid }
+    }
+
+    pub fn array(&self) -> &A {
+        &self.array
+    }
+
+    pub fn len(&self) -> usize {
+        self.array.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.array.is_empty()
+    }
+
+    /// Indices of the valid values.
+    pub fn valid_range(&self) -> Range<usize> {
+        self.valid.clone()
+    }
+
+    pub fn lower_bound(&self, x: &A::Item) -> Result<usize, usize> {
+        self.lower_bound_by(|y| y.arrow_cmp(x))
+    }
+
+    pub fn lower_bound_by<F: FnMut(A::Item) -> Ordering>(&self, mut f: F) -> Result<usize, usize> {
+        let pos = lower_bound_index(self.valid.len(), |i| f(self.array.value(self.valid.start + i)));
+        self.position(pos.unwrap_or_else(|e| e))
+    }
+
+    pub fn upper_bound(&self, x: &A::Item) -> Result<usize, usize> {
+        self.upper_bound_by(|y| y.arrow_cmp(x))
+    }
+
+    pub fn upper_bound_by<F: FnMut(A::Item) -> Ordering>(&self, mut f: F) -> Result<usize, usize> {
+        let pos = upper_bound_index(self.valid.len(), |i| f(self.array.value(self.valid.start + i)));
+        self.position(pos.unwrap_or_else(|e| e))
+    }
+
+    /// Indices of the valid values equal to `x`.
+    pub fn equal_range(&self, x: &A::Item) -> Range<usize> {
+        let lo = self.lower_bound(x).unwrap_or_else(|e| e);
+        let hi = self.upper_bound(x).unwrap_or_else(|e| e);
+        lo..hi
+    }
+
+    /// For each value, the index of its first occurrence among the valid values.
+    pub fn lookup<I: IntoIterator<Item = A::Item>>(&self, values: I) -> Vec<Option<usize>> {
+        values
+            .into_iter()
+            .map(|x| {
+                let i = self.lower_bound(&x).ok().filter(|&i| i < self.valid.end)?;
+                (self.array.value(i).arrow_cmp(&x) == Ordering::Equal).then_some(i)
+            })
+            .collect()
+    }
+
+    /// Bin indices of `values` against the valid values as edges, as [`digitize`](crate::digitize):
+    /// from `0` below the first edge to the number of valid values past the last.
+    pub fn digitize(&self, values: &[A::Item], right_closed: bool) -> Vec<usize> {
+        let bound = |x| {
+            if right_closed {
+                self.lower_bound(x)
+            } else {
+                self.upper_bound(x)
+            }
+        };
+        values
+            .iter()
+            .map(|x| bound(x).unwrap_or_else(|e| e) - self.valid.start)
+            .collect()
+    }
+
+    /// Array index of position `pos` among the valid values, with the `Result` convention.
+    fn position(&self, pos: usize) -> Result<usize, usize> {
+        let idx = self.valid.start + pos;
+        // with nulls last, the slot past the last valid value is a null, not a position
+        if pos == self.valid.len() {
+            Err(idx)
+        } else {
+            Ok(idx)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::types::Int8Type;
+    use arrow_array::{BinaryArray, DictionaryArray, Float64Array, Int32Array, StringArray};
+    use bound_stl::{LowerBound, NullOrder, SortedArrow, UpperBound};
+
+    #[test]
+    fn test_sorted_arrow() {
+        let plain = [-1.5, 0.0, 0.0, 2.5, 7.0];
+        let with_nulls = |first: bool| {
+            let mut v = plain.iter().copied().map(Some).collect::<Vec<_>>();
+            let nulls = [None, None];
+            let at = if first { 0 } else { v.len() };
+            v.splice(at..at, nulls);
+            Float64Array::from(v)
+        };
+        for (first, order, offset) in [(true, NullOrder::NullsFirst, 2), (false, NullOrder::NullsLast, 0)] {
+            let array = with_nulls(first);
+            let sorted = SortedArrow::new(&array, order);
+            assert_eq!(sorted.valid_range(), offset..offset + 5);
+            for x in [-2.0, -1.5, 0.0, 1.0, 7.0, 8.0] {
+                let lo = plain.partition_point(|&y| y < x) + offset;
+                let hi = plain.partition_point(|&y| y <= x) + offset;
+                let wrap = |p| if p == offset + 5 { Err(p) } else { Ok(p) };
+                assert_eq!(sorted.lower_bound(&x), wrap(lo), "x = {x}");
+                assert_eq!(sorted.upper_bound(&x), wrap(hi), "x = {x}");
+            }
+            let bins = sorted.digitize(&[-3.0, 0.0, 9.0], true);
+            assert_eq!(bins, [0, 1, 5]);
+        }
+
+        let bytes = BinaryArray::from(vec![&b"a"[..], b"ab", b"b"]);
+        let sorted = SortedArrow::new(&bytes, NullOrder::NullsLast);
+        assert_eq!(sorted.lookup([&b"ab"[..], b"c"]), [Some(1), None]);
+        assert_eq!(sorted.upper_bound(&&b"b"[..]), Err(3));
+
+        // keys are sorted by value, the dictionary itself is not
+        let dict = DictionaryArray::<Int8Type>::from_iter([None, Some("k"), Some("m"), Some("m"), Some("q")]);
+        let typed = dict.downcast_dict::<StringArray>().unwrap();
+        let sorted = SortedArrow::new(typed, NullOrder::NullsFirst);
+        let words = ["k", "m", "m", "q"];
+        for q in ["a", "k", "l", "m", "q", "z"] {
+            let shift = |r: Result<usize, usize>| r.map(|i| i + 1).map_err(|e| e + 1);
+            assert_eq!(sorted.lower_bound(&q), shift(words.lower_bound(&q)), "q = {q}");
+            assert_eq!(sorted.upper_bound(&q), shift(words.upper_bound(&q)), "q = {q}");
+        }
+
+        // the null slot holds a zero that must not be found
+        let ints = Int32Array::from(vec![Some(-3), Some(-2), None]);
+        let sorted = SortedArrow::new(&ints, NullOrder::NullsLast);
+        assert_eq!(sorted.lower_bound(&0), Err(2));
+        assert_eq!(sorted.upper_bound(&-2), Err(2));
+        assert_eq!(sorted.lookup([0, -2]), [None, Some(1)]);
+
+        let empty = Float64Array::from(Vec::<f64>::new());
+        assert_eq!(SortedArrow::new(&empty, NullOrder::NullsLast).lower_bound(&1.0), Err(0));
+    }
+}
@@ -0,0 +1,93 @@
+use crate::upper_bound_index;
+
+/// Bits with constant-time `rank1`, one cumulative count per 64-bit word,
+/// and `select` by binary search over those counts.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RankBits {
+    words: Vec<u64>,
+    ranks: Vec<usize>,
+}
+
+impl RankBits {
+    pub(crate) fn new(bits: impl ExactSizeIterator<Item = bool>) -> Self {
+        let mut words = vec![0u64; bits.len() / 64 + 1];
+        for (i, b) in bits.enumerate() {
+            words[i / 64] |= (b as u64) << (i % 64);
+        }
+        let mut ranks = Vec::with_capacity(words.len());
+        let mut acc = 0;
+        for w in &words {
+            ranks.push(acc);
+            acc += w.count_ones() as usize;
+        }
+        RankBits { words, ranks }
+    }
+
+    pub(crate) fn get(&self, i: usize) -> bool {
+        self.words[i / 64] >> (i % 64) & 1 == 1
+    }
+
+    /// Ones in `[0, i)`.
+    pub(crate) fn rank1(&self, i: usize) -> usize {
+        let mask = (1u64 << (i % 64)) - 1;
+        self.ranks[i / 64] + (self.words[i / 64] & mask).count_ones() as usize
+    }
+
+    /// Zeros in `[0, i)`.
+    pub(crate) fn rank0(&self, i: usize) -> usize {
+        i - self.rank1(i)
+    }
+
+    /// Position of the `k`-th zero, counting from zero.
+    pub(crate) fn select0(&self, k: usize) -> usize {
+        let zeros_before = |w: usize| w * 64 - self.ranks[w];
+        let w = upper_bound_index(self.ranks.len(), |w| zeros_before(w).cmp(&k)).unwrap_or_else(|e| e) - 1;
+        w * 64 + nth_set_bit(!self.words[w], k - zeros_before(w))
+    }
+}
+
+fn nth_set_bit(mut word: u64, n: usize) -> usize {
+    for _ in 0..n {
+        word &= word - 1;
+    }
+    word.trailing_zeros() as usize
+}
+
+/// Unsigned integers below a fixed bound, each packed into the bits that bound needs.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PackedInts {
+    words: Vec<usize>,
+    width: u32,
+}
+
+impl PackedInts {
+    /// Packs `values`, which must all be at most `max`.
+    pub(crate) fn new(values: impl ExactSizeIterator<Item = usize>, max: usize) -> Self {
+        let width = usize::BITS - max.leading_zeros();
+        let mut words = vec![0; (values.len() * width as usize).div_ceil(usize::BITS as usize) + 1];
+        for (i, v) in values.enumerate() {
+            debug_assert!(v <= max, "packed value out of range");
+            let (w, off) = Self::locate(i, width);
+            words[w] |= v << off;
+            if off + width > usize::BITS {
+                words[w + 1] |= v >> (usize::BITS - off);
+            }
+        }
+        PackedInts { words, width }
+    }
+
+    pub(crate) fn get(&self, i: usize) -> usize {
+        let (w, off) = Self::locate(i, self.width);
+        let mut v = self.words[w] >> off;
+        if off + self.width > usize::BITS {
+            v |= self.words[w + 1] << (usize::BITS - off);
+        }
+        v & usize::MAX.checked_shr(usize::BITS - self.width).unwrap_or(0)
+    }
+
+    /// Word index and bit offset of value `i`.
+    fn locate(i: usize, width: u32) -> (usize, u32) {
+        let bit = i * width as usize;
+        (bit / usize::BITS as usize, (bit % usize::BITS as usize) as u32)
+    }
+}
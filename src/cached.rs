@@ -0,0 +1,251 @@
+use crate::extsort::{FixedRecord, SortedFile};
+use crate::{LowerBound, UpperBound};
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::io;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Lower,
+    Upper,
+}
+
+/// Last use and result of a cached search.
+type Slot = Option<(u64, Result<usize, usize>)>;
+
+/// A search backend with a small LRU cache of recent `lower_bound` / `upper_bound` results.
+///
+/// Works over anything implementing `LowerBound` and `UpperBound`, and over a
+/// [`SortedFile`](crate::extsort::SortedFile), where a cache hit saves `O(log n)` reads.
+/// Searches take `&mut self` to update the cache. Mutate the backend through
+/// [`modify`](Self::modify), which drops the whole cache, or through
+/// [`backend_mut`](Self::backend_mut) followed by [`invalidate_from`](Self::invalidate_from)
+/// with each inserted or removed key, which keeps most results for smaller keys.
+/// ```
+/// # use bound_stl::CachedSearcher;
+/// let mut ids = CachedSearcher::new(vec![10, 20, 30, 40], 2);
+/// assert_eq!(ids.lower_bound(&25), Ok(2));
+/// assert_eq!(ids.lower_bound(&25), Ok(2));
+/// assert_eq!((ids.hits(), ids.misses()), (1, 1));
+///
+/// ids.backend_mut().insert(0, 5);
+/// ids.invalidate_from(&5);
+/// assert_eq!(ids.lower_bound(&25), Ok(3));
+/// ```
+#[derive(Debug, Clone)]
+pub struct CachedSearcher<S, T> {
+    backend: S,
+    capacity: usize,
+    tick: u64,
+    /// cached searches of each key, per side
+    entries: BTreeMap<T, [Slot; 2]>,
+    /// cached keys by last use, oldest first
+    recency: BTreeMap<u64, (T, Side)>,
+    hits: u64,
+    misses: u64,
+}
+
+impl<S, T: Ord + Clone> CachedSearcher<S, T> {
+    /// Caches the results of up to `capacity` searches.
+    pub fn new(backend: S, capacity: usize) -> Self {
+        assert!(capacity > 0, "cache capacity must not be zero");
+        CachedSearcher {
+            backend,
+            capacity,
+            tick: 0,
+            entries: BTreeMap::new(),
+            recency: BTreeMap::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    pub fn backend(&self) -> &S {
+        &self.backend
+    }
+
+    /// The backend, for changes the caller reports with [`invalidate_from`](Self::invalidate_from).
+    pub fn backend_mut(&mut self) -> &mut S {
+        &mut self.backend
+    }
+
+    /// Changes the backend through `f` and drops every cached result.
+    pub fn modify<R, F: FnOnce(&mut S) -> R>(&mut self, f: F) -> R {
+        self.invalidate_all();
+        f(&mut self.backend)
+    }
+
+    /// Drops the cached results that inserting or removing `key` can change: those of the
+    /// keys not less than `key`, and those of smaller keys that end where `key` sits.
+    pub fn invalidate_from(&mut self, key: &T) {
+        for (_, sides) in self.entries.split_off(key) {
+            for (tick, _) in sides.into_iter().flatten() {
+                self.recency.remove(&tick);
+            }
+        }
+        // a smaller key keeps its position, but if that is where `key` was inserted or
+        // removed, whether it is past the end can change; such results end furthest right
+        let end = |slot: &Slot| slot.map(|(_, res)| res.unwrap_or_else(|e| e));
+        let Some(last) = self.entries.values().flatten().filter_map(end).max() else {
+            return;
+        };
+        for sides in self.entries.values_mut() {
+            for slot in sides.iter_mut().filter(|slot| end(slot) == Some(last)) {
+                let (tick, _) = slot.take().expect("slot was just matched");
+                self.recency.remove(&tick);
+            }
+        }
+        self.entries.retain(|_, sides| sides.iter().any(Option::is_some));
+    }
+
+    pub fn invalidate_all(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+    }
+
+    /// Number of cached results.
+    pub fn cached(&self) -> usize {
+        self.recency.len()
+    }
+
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    pub fn into_inner(self) -> S {
+        self.backend
+    }
+
+    fn search<E, F>(&mut self, side: Side, x: &T, f: F) -> Result<Result<usize, usize>, E>
+    where
+        F: FnOnce(&S) -> Result<Result<usize, usize>, E>,
+    {
+        self.tick += 1;
+        if let Some((tick, res)) = self.entries.get_mut(x).and_then(|e| e[side as usize].as_mut()) {
+            let key = self.recency.remove(tick).expect("cache recency out of sync");
+            self.recency.insert(self.tick, key);
+            *tick = self.tick;
+            self.hits += 1;
+            return Ok(*res);
+        }
+        self.misses += 1;
+        let res = f(&self.backend)?;
+        if self.recency.len() == self.capacity {
+            self.evict_oldest();
+        }
+        self.entries.entry(x.clone()).or_default()[side as usize] = Some((self.tick, res));
+        self.recency.insert(self.tick, (x.clone(), side));
+        Ok(res)
+    }
+
+    fn evict_oldest(&mut self) {
+        if let Some((_, (key, side))) = self.recency.pop_first() {
+            let sides = self.entries.get_mut(&key).expect("cache entries out of sync");
+            sides[side as usize] = None;
+            if sides.iter().all(Option::is_none) {
+                self.entries.remove(&key);
+            }
+        }
+    }
+}
+
+impl<S, T> CachedSearcher<S, T>
+where
+    S: LowerBound<Item = T> + UpperBound<Item = T>,
+    T: Ord + Clone,
+{
+    pub fn lower_bound(&mut self, x: &T) -> Result<usize, usize> {
+        let res = self.search(Side::Lower, x, |s| Ok::<_, Infallible>(s.lower_bound(x)));
+        res.unwrap_or_else(|e| match e {})
+    }
+
+    pub fn upper_bound(&mut self, x: &T) -> Result<usize, usize> {
+        let res = self.search(Side::Upper, x, |s| Ok::<_, Infallible>(s.upper_bound(x)));
+        res.unwrap_or_else(|e| match e {})
+    }
+}
+
+/// Read errors are returned and not cached.
+impl<T: FixedRecord + Ord + Clone> CachedSearcher<SortedFile<T>, T> {
+    pub fn lower_bound(&mut self, x: &T) -> io::Result<Result<usize, usize>> {
+        self.search(Side::Lower, x, |s| s.lower_bound(x))
+    }
+
+    pub fn upper_bound(&mut self, x: &T) -> io::Result<Result<usize, usize>> {
+        self.search(Side::Upper, x, |s| s.upper_bound(x))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::extsort::SortedFile;
+    use bound_stl::{CachedSearcher, LowerBound, UpperBound};
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_cached_searcher() {
+        let mut v = (0..50).map(|x| x * 2).collect::<Vec<i32>>();
+        let mut cached = CachedSearcher::new(v.clone(), 4);
+        for x in [7, 8, 7, 9, 11, 7, 13, 8, 8] {
+            assert_eq!(cached.lower_bound(&x), v.lower_bound(&x));
+            assert_eq!(cached.upper_bound(&x), v.upper_bound(&x));
+            assert!(cached.cached() <= 4);
+        }
+        assert_eq!((cached.hits(), cached.misses()), (4, 14));
+        // only the 4 most recent searches are cached
+        assert_eq!(cached.upper_bound(&13), Ok(7));
+        assert_eq!(cached.lower_bound(&9), Ok(5));
+        assert_eq!((cached.hits(), cached.misses()), (5, 15));
+
+        for x in [3, 51] {
+            cached.backend_mut().insert(v.lower_bound(&x).unwrap_or_else(|e| e), x);
+            v.insert(v.lower_bound(&x).unwrap_or_else(|e| e), x);
+            cached.invalidate_from(&x);
+            for y in [0, 2, 3, 4, 50, 51, 52, 200] {
+                assert_eq!(cached.lower_bound(&y), v.lower_bound(&y), "y = {y}");
+                assert_eq!(cached.upper_bound(&y), v.upper_bound(&y), "y = {y}");
+            }
+        }
+        // past the current maximum: the position of 101 stays, but it stops being the end
+        for (x, push) in [(300, true), (300, false)] {
+            assert_eq!(cached.lower_bound(&101), v.lower_bound(&101));
+            assert_eq!(cached.upper_bound(&100), v.upper_bound(&100));
+            if push {
+                cached.backend_mut().push(x);
+                v.push(x);
+            } else {
+                cached.backend_mut().pop();
+                v.pop();
+            }
+            cached.invalidate_from(&x);
+            assert_eq!(cached.lower_bound(&101), v.lower_bound(&101));
+            assert_eq!(cached.upper_bound(&100), v.upper_bound(&100));
+            assert_eq!(cached.lower_bound(&0), Ok(0));
+        }
+
+        cached.modify(|b| b.retain(|x| x % 3 != 0));
+        assert_eq!(cached.cached(), 0);
+        v.retain(|x| x % 3 != 0);
+        assert_eq!(cached.upper_bound(&51), v.upper_bound(&51));
+        assert_eq!(cached.into_inner(), v);
+
+        let set = BTreeSet::from([1, 5, 9]);
+        let mut cached = CachedSearcher::new(set, 1);
+        assert_eq!(cached.lower_bound(&5), Ok(1));
+        assert_eq!(cached.upper_bound(&9), Err(3));
+        assert_eq!((cached.hits(), cached.cached()), (0, 1));
+
+        let path = std::env::temp_dir().join(format!("bound-stl-cached-{}.bin", std::process::id()));
+        std::fs::write(&path, (0..100u32).flat_map(u32::to_le_bytes).collect::<Vec<_>>()).unwrap();
+        let mut cached = CachedSearcher::new(SortedFile::<u32>::open(&path).unwrap(), 8);
+        assert_eq!(cached.lower_bound(&40).unwrap(), Ok(40));
+        assert_eq!(cached.upper_bound(&99).unwrap(), Err(100));
+        assert_eq!(cached.lower_bound(&40).unwrap(), Ok(40));
+        assert_eq!(cached.hits(), 1);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,305 @@
+use crate::lower_bound_index;
+use csv::{ReaderBuilder, StringRecord};
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::ops::{Bound, Range, RangeBounds};
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Scans a CSV file sorted by one column into a [`CsvIndex`].
+#[derive(Debug, Clone)]
+pub struct CsvIndexBuilder {
+    column: usize,
+    has_headers: bool,
+    delimiter: u8,
+    sample_every: usize,
+}
+
+impl CsvIndexBuilder {
+    /// A builder for files sorted by the field at `column`, counted from 0.
+    pub fn new(column: usize) -> Self {
+        CsvIndexBuilder {
+            column,
+            has_headers: true,
+            delimiter: b',',
+            sample_every: 64,
+        }
+    }
+
+    /// Whether the first row is a header row, kept out of the index. Defaults to true.
+    pub fn has_headers(mut self, yes: bool) -> Self {
+        self.has_headers = yes;
+        self
+    }
+
+    /// Defaults to `b','`.
+    pub fn delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Keeps the key of every `rows`-th row in memory; a search reads about `log2(rows)` rows
+    /// from the file. Defaults to 64.
+    pub fn sample_every(mut self, rows: usize) -> Self {
+        assert!(rows > 0, "sample interval must not be zero");
+        self.sample_every = rows;
+        self
+    }
+
+    /// Reads the whole file once and opens the index. The rows are assumed to be sorted by
+    /// the key column.
+    pub fn build<P: AsRef<Path>>(&self, path: P) -> io::Result<CsvIndex> {
+        let file = File::open(path)?;
+        let mut reader = ReaderBuilder::new()
+            .has_headers(self.has_headers)
+            .delimiter(self.delimiter)
+            .from_reader(BufReader::new(file.try_clone()?));
+        let headers = if self.has_headers {
+            Some(reader.headers()?.clone())
+        } else {
+            None
+        };
+        let (mut offsets, mut samples) = (Vec::new(), Vec::new());
+        let mut record = StringRecord::new();
+        while reader.read_record(&mut record)? {
+            let row = offsets.len();
+            offsets.push(record.position().map_or(0, |p| p.byte()));
+            if row.is_multiple_of(self.sample_every) {
+                samples.push(key_of(&record, self.column)?.to_owned());
+            }
+        }
+        offsets.push(reader.position().byte());
+        Ok(CsvIndex {
+            file: Mutex::new(file),
+            column: self.column,
+            delimiter: self.delimiter,
+            sample_every: self.sample_every,
+            headers,
+            offsets,
+            samples,
+        })
+    }
+}
+
+/// A CSV file sorted by one column, searched in place.
+///
+/// The index holds the byte offset of every row and the key of every `sample_every`-th row.
+/// A search narrows down to one sample interval in memory, then binary searches the rows
+/// of that interval by reading them from the file, so queries never load the whole file.
+/// Keys compare as strings unless a `_by` method is given another order; the bound methods
+/// follow the `LowerBound` / `UpperBound` `Result` convention, wrapped in an `io::Result`.
+/// ```
+/// # use bound_stl::CsvIndexBuilder;
+/// let path = std::env::temp_dir().join(format!("csv-index-doc-{}.csv", std::process::id()));
+/// std::fs::write(&path, "id,city\n3,Berlin\n1,Lima\n2,Lima\n5,Oslo\n4,\"Paris, FR\"\n")?;
+/// let index = CsvIndexBuilder::new(1).sample_every(2).build(&path)?;
+/// assert_eq!(index.len(), 5);
+/// assert_eq!(index.lower_bound("Lima")?, Ok(1));
+/// let rows = index.range("L".."P")?;
+/// assert_eq!(rows, 1..4);
+/// let ids = index.read_rows(rows)?.iter().map(|r| r[0].to_owned()).collect::<Vec<_>>();
+/// assert_eq!(ids, ["1", "2", "5"]);
+/// assert_eq!(&index.row(4)?[1], "Paris, FR");
+/// # std::fs::remove_file(&path)?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct CsvIndex {
+    file: Mutex<File>,
+    column: usize,
+    delimiter: u8,
+    sample_every: usize,
+    headers: Option<StringRecord>,
+    /// start of every row, then the end of the last one
+    offsets: Vec<u64>,
+    /// `samples[j]`: key of row `j * sample_every`
+    samples: Vec<String>,
+}
+
+impl CsvIndex {
+    pub fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The header row, if the file was indexed with one.
+    pub fn headers(&self) -> Option<&StringRecord> {
+        self.headers.as_ref()
+    }
+
+    /// Reads row `idx`.
+    pub fn row(&self, idx: usize) -> io::Result<StringRecord> {
+        if idx >= self.len() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "row index out of bounds"));
+        }
+        self.read_rows(idx..idx + 1)?
+            .pop()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "row vanished from the file"))
+    }
+
+    /// Reads the rows at positions `range` with one seek, in order.
+    pub fn read_rows(&self, range: Range<usize>) -> io::Result<Vec<StringRecord>> {
+        let range = range.start.min(self.len())..range.end.min(self.len());
+        if range.is_empty() {
+            return Ok(Vec::new());
+        }
+        let (start, end) = (self.offsets[range.start], self.offsets[range.end]);
+        let mut buf = vec![0; (end - start) as usize];
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        file.seek(SeekFrom::Start(start))?;
+        file.read_exact(&mut buf)?;
+        drop(file);
+        ReaderBuilder::new()
+            .has_headers(false)
+            .delimiter(self.delimiter)
+            .from_reader(&buf[..])
+            .records()
+            .map(|r| r.map_err(io::Error::from))
+            .collect()
+    }
+
+    /// Index of the first row whose key is not less than `key`.
+    pub fn lower_bound(&self, key: &str) -> io::Result<Result<usize, usize>> {
+        self.lower_bound_by(|k| k.cmp(key))
+    }
+
+    pub fn lower_bound_by<F>(&self, mut f: F) -> io::Result<Result<usize, usize>>
+    where
+        F: FnMut(&str) -> Ordering,
+    {
+        self.search(|k| f(k) == Ordering::Less)
+    }
+
+    /// Index of the first row whose key is greater than `key`.
+    pub fn upper_bound(&self, key: &str) -> io::Result<Result<usize, usize>> {
+        self.upper_bound_by(|k| k.cmp(key))
+    }
+
+    pub fn upper_bound_by<F>(&self, mut f: F) -> io::Result<Result<usize, usize>>
+    where
+        F: FnMut(&str) -> Ordering,
+    {
+        self.search(|k| f(k) != Ordering::Greater)
+    }
+
+    /// Index range of the rows whose key lies in `range`.
+    pub fn range<'k, R: RangeBounds<&'k str>>(&self, range: R) -> io::Result<Range<usize>> {
+        let start = match range.start_bound() {
+            Bound::Included(k) => self.lower_bound(k)?,
+            Bound::Excluded(k) => self.upper_bound(k)?,
+            Bound::Unbounded => Ok(0),
+        };
+        let end = match range.end_bound() {
+            Bound::Included(k) => self.upper_bound(k)?,
+            Bound::Excluded(k) => self.lower_bound(k)?,
+            Bound::Unbounded => Ok(self.len()),
+        };
+        let (start, end) = (start.unwrap_or_else(|e| e), end.unwrap_or_else(|e| e));
+        Ok(start..end.max(start))
+    }
+
+    /// Position of the first row whose key fails `before`, which holds for a prefix of the rows.
+    fn search<F>(&self, mut before: F) -> io::Result<Result<usize, usize>>
+    where
+        F: FnMut(&str) -> bool,
+    {
+        // rows up to the last sample before the target are all before it, and the next
+        // sampled row is not
+        let block = self.samples.partition_point(|k| before(k));
+        let lo = match block {
+            0 => 0,
+            _ => (block - 1) * self.sample_every + 1,
+        };
+        let hi = (block * self.sample_every).min(self.len());
+        let mut err = None;
+        let found = lower_bound_index(hi.saturating_sub(lo), |i| {
+            if err.is_some() {
+                return Ordering::Greater;
+            }
+            match self.row(lo + i).and_then(|r| key_of(&r, self.column).map(&mut before)) {
+                Ok(true) => Ordering::Less,
+                Ok(false) => Ordering::Greater,
+                Err(e) => {
+                    err = Some(e);
+                    Ordering::Greater
+                }
+            }
+        });
+        let pos = lo + found.unwrap_or_else(|e| e);
+        match err {
+            Some(e) => Err(e),
+            None if pos < self.len() => Ok(Ok(pos)),
+            None => Ok(Err(pos)),
+        }
+    }
+}
+
+fn key_of(record: &StringRecord, column: usize) -> io::Result<&str> {
+    record
+        .get(column)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "row has no key column"))
+}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::{CsvIndexBuilder, LowerBound, UpperBound};
+
+    #[test]
+    fn test_csv_index() {
+        let path = std::env::temp_dir().join(format!("bound-stl-csv-{}.csv", std::process::id()));
+        let keys = (0..200).map(|i| format!("k{:03}", i / 3 * 2)).collect::<Vec<_>>();
+        let mut data = String::from("n,key,note\r\n");
+        for (i, k) in keys.iter().enumerate() {
+            data += &format!("{i},{k},\"line {i}\nwith \"\"quotes\"\", commas\"\r\n");
+        }
+        std::fs::write(&path, &data).unwrap();
+
+        for every in [1, 5, 64, 1000] {
+            let index = CsvIndexBuilder::new(1).sample_every(every).build(&path).unwrap();
+            assert_eq!(index.len(), keys.len());
+            assert_eq!(&index.headers().unwrap()[2], "note");
+            for q in ["", "k000", "k001", "k066", "k131", "k132", "k133", "z"] {
+                let q = q.to_owned();
+                assert_eq!(index.lower_bound(&q).unwrap(), keys.lower_bound(&q), "q = {q}");
+                assert_eq!(index.upper_bound(&q).unwrap(), keys.upper_bound(&q), "q = {q}");
+            }
+            let rows = index.range("k010"..="k020").unwrap();
+            assert_eq!(rows, 15..33);
+            let read = index.read_rows(rows).unwrap();
+            assert!(read
+                .iter()
+                .zip(15..)
+                .all(|(r, i)| r[0] == i.to_string() && r[1] == keys[i]));
+            assert_eq!(&index.row(7).unwrap()[2], "line 7\nwith \"quotes\", commas");
+        }
+
+        // no header, another delimiter, a numeric order
+        std::fs::write(&path, "9;x\n10;y\n10;z\n200;w\n").unwrap();
+        let index = CsvIndexBuilder::new(0)
+            .has_headers(false)
+            .delimiter(b';')
+            .sample_every(2)
+            .build(&path)
+            .unwrap();
+        assert!(index.headers().is_none());
+        let num = |k: &str| k.parse::<u32>().unwrap();
+        assert_eq!(index.lower_bound_by(|k| num(k).cmp(&10)).unwrap(), Ok(1));
+        assert_eq!(index.upper_bound_by(|k| num(k).cmp(&10)).unwrap(), Ok(3));
+        assert_eq!(index.upper_bound_by(|k| num(k).cmp(&500)).unwrap(), Err(4));
+        assert!(index.row(4).is_err());
+
+        std::fs::write(&path, "key\n").unwrap();
+        let empty = CsvIndexBuilder::new(0).build(&path).unwrap();
+        assert!(empty.is_empty());
+        assert_eq!(empty.lower_bound("a").unwrap(), Err(0));
+        assert_eq!(empty.range(..).unwrap(), 0..0);
+        assert!(CsvIndexBuilder::new(3).build(&path).is_ok());
+        std::fs::write(&path, "key\na\n").unwrap();
+        assert!(CsvIndexBuilder::new(3).build(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
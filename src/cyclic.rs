@@ -0,0 +1,152 @@
+use crate::{lower_bound_index, upper_bound_index};
+use std::cmp::Ordering;
+use std::ops::Range;
+
+/// Successor searches over a sorted slice of keys from a cyclic domain, such as seconds of
+/// the day, angles or hash-ring positions, where the first key follows the last one.
+///
+/// The keys and the query are assumed to lie in the same period, e.g. reduced with
+/// `rem_euclid`. The searches return `None` only for an empty slice.
+/// ```
+/// # use bound_stl::CyclicBound;
+/// // the nodes of a consistent-hashing ring, by position
+/// let ring = [(120u16, "a"), (30_000, "b"), (61_000, "c")];
+/// let owner = |hash: u16| ring[ring.cyclic_lower_bound_by_key(&hash, |n| n.0).unwrap()].1;
+/// assert_eq!(owner(100), "a");
+/// assert_eq!(owner(30_000), "b");
+/// assert_eq!(owner(62_000), "a");
+///
+/// // departures after 23:00 until 01:00, in minutes of the day
+/// let departures = [15, 50, 70, 600, 1390, 1420];
+/// assert_eq!(departures.cyclic_upper_bound(&1420), Some(0));
+/// assert_eq!(departures.cyclic_range(&1380, &60), (4..6, 0..2));
+/// ```
+pub trait CyclicBound {
+    type Item;
+
+    /// Index of the first element not less than `x`, or the first element if there is none.
+    fn cyclic_lower_bound(&self, x: &Self::Item) -> Option<usize>;
+
+    fn cyclic_lower_bound_by<'a, F>(&'a self, f: F) -> Option<usize>
+    where
+        F: FnMut(&'a Self::Item) -> Ordering;
+
+    fn cyclic_lower_bound_by_key<'a, K, F>(&'a self, k: &K, f: F) -> Option<usize>
+    where
+        F: FnMut(&'a Self::Item) -> K,
+        K: Ord;
+
+    /// Index of the first element greater than `x`, or the first element if there is none.
+    fn cyclic_upper_bound(&self, x: &Self::Item) -> Option<usize>;
+
+    fn cyclic_upper_bound_by<'a, F>(&'a self, f: F) -> Option<usize>
+    where
+        F: FnMut(&'a Self::Item) -> Ordering;
+
+    fn cyclic_upper_bound_by_key<'a, K, F>(&'a self, k: &K, f: F) -> Option<usize>
+    where
+        F: FnMut(&'a Self::Item) -> K,
+        K: Ord;
+
+    /// Index ranges of the elements on the arc from `from` (inclusive) forward to `to`
+    /// (exclusive). When the arc wraps past the end of the period, the second range holds
+    /// the elements after the wrap; otherwise it is empty. The arc is empty if `from == to`.
+    fn cyclic_range(&self, from: &Self::Item, to: &Self::Item) -> (Range<usize>, Range<usize>);
+}
+
+impl<T: Ord> CyclicBound for [T] {
+    type Item = T;
+
+    fn cyclic_lower_bound(&self, x: &T) -> Option<usize> {
+        self.cyclic_lower_bound_by(|y| y.cmp(x))
+    }
+
+    fn cyclic_lower_bound_by<'a, F>(&'a self, mut f: F) -> Option<usize>
+    where
+        F: FnMut(&'a T) -> Ordering,
+    {
+        wrap(self.len(), lower_bound_index(self.len(), |i| f(&self[i])))
+    }
+
+    fn cyclic_lower_bound_by_key<'a, K, F>(&'a self, k: &K, mut f: F) -> Option<usize>
+    where
+        F: FnMut(&'a T) -> K,
+        K: Ord,
+    {
+        self.cyclic_lower_bound_by(|y| f(y).cmp(k))
+    }
+
+    fn cyclic_upper_bound(&self, x: &T) -> Option<usize> {
+        self.cyclic_upper_bound_by(|y| y.cmp(x))
+    }
+
+    fn cyclic_upper_bound_by<'a, F>(&'a self, mut f: F) -> Option<usize>
+    where
+        F: FnMut(&'a T) -> Ordering,
+    {
+        wrap(self.len(), upper_bound_index(self.len(), |i| f(&self[i])))
+    }
+
+    fn cyclic_upper_bound_by_key<'a, K, F>(&'a self, k: &K, mut f: F) -> Option<usize>
+    where
+        F: FnMut(&'a T) -> K,
+        K: Ord,
+    {
+        self.cyclic_upper_bound_by(|y| f(y).cmp(k))
+    }
+
+    fn cyclic_range(&self, from: &T, to: &T) -> (Range<usize>, Range<usize>) {
+        let len = self.len();
+        let lo = lower_bound_index(len, |i| self[i].cmp(from)).unwrap_or_else(|e| e);
+        let hi = lower_bound_index(len, |i| self[i].cmp(to)).unwrap_or_else(|e| e);
+        match from.cmp(to) {
+            Ordering::Less => (lo..hi, len..len),
+            Ordering::Equal => (lo..lo, len..len),
+            Ordering::Greater => (lo..len, 0..hi),
+        }
+    }
+}
+
+/// Past the end means back at the front.
+fn wrap(len: usize, res: Result<usize, usize>) -> Option<usize> {
+    match res {
+        Ok(i) => Some(i),
+        Err(_) if len > 0 => Some(0),
+        Err(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::CyclicBound;
+
+    #[test]
+    fn test_cyclic_bound() {
+        let angles = [0u16, 45, 45, 180, 350];
+        let next = |x: u16, strict: bool| {
+            let mut i = (0..angles.len()).filter(|&i| if strict { angles[i] > x } else { angles[i] >= x });
+            i.next().unwrap_or(0)
+        };
+        for x in 0..360 {
+            assert_eq!(angles.cyclic_lower_bound(&x), Some(next(x, false)), "x = {x}");
+            assert_eq!(angles.cyclic_upper_bound(&x), Some(next(x, true)), "x = {x}");
+        }
+        assert_eq!(angles.cyclic_upper_bound_by_key(&10, |a| a / 10), Some(3));
+        assert_eq!(angles.cyclic_lower_bound_by(|a| a.cmp(&351)), Some(0));
+
+        let on_arc = |from: u16, to: u16| {
+            let (a, b) = angles.cyclic_range(&from, &to);
+            a.chain(b).map(|i| angles[i]).collect::<Vec<_>>()
+        };
+        assert_eq!(on_arc(40, 180), [45, 45]);
+        assert_eq!(on_arc(300, 50), [350, 0, 45, 45]);
+        assert!(on_arc(351, 0).is_empty());
+        assert!(on_arc(180, 180).is_empty());
+        assert_eq!(angles.cyclic_range(&350, &1), (4..5, 0..1));
+
+        let empty: [u16; 0] = [];
+        assert_eq!(empty.cyclic_lower_bound(&3), None);
+        assert_eq!(empty.cyclic_upper_bound(&3), None);
+        assert_eq!(empty.cyclic_range(&5, &1), (0..0, 0..0));
+    }
+}
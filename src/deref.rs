@@ -0,0 +1,57 @@
+use crate::{lower_bound_index, upper_bound_index};
+use std::ops::Deref;
+
+/// Bounds over a slice of smart pointers (`Box`, `Rc`, `Arc`, `&T`, ...) sorted by the
+/// pointed-to value, queried with a reference to that value; assume the slice is sorted.
+/// ```
+/// # use bound_stl::DerefBound;
+/// # use std::sync::Arc;
+/// let v = vec![Arc::new("ant".to_string()), Arc::new("bee".to_string()), Arc::new("cat".to_string())];
+/// let bee = "bee".to_string();
+/// assert_eq!(v.lower_bound_deref(&bee), Ok(1));
+/// assert_eq!(v.upper_bound_deref(&bee), Ok(2));
+/// assert_eq!(v.upper_bound_deref(&"cow".to_string()), Err(3));
+/// ```
+pub trait DerefBound {
+    type Target: ?Sized;
+    fn lower_bound_deref(&self, x: &Self::Target) -> Result<usize, usize>;
+    fn upper_bound_deref(&self, x: &Self::Target) -> Result<usize, usize>;
+}
+
+impl<P> DerefBound for [P]
+where
+    P: Deref,
+    P::Target: Ord,
+{
+    type Target = P::Target;
+
+    fn lower_bound_deref(&self, x: &Self::Target) -> Result<usize, usize> {
+        lower_bound_index(self.len(), |i| (*self[i]).cmp(x))
+    }
+
+    fn upper_bound_deref(&self, x: &Self::Target) -> Result<usize, usize> {
+        upper_bound_index(self.len(), |i| (*self[i]).cmp(x))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::DerefBound;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_deref_bound() {
+        let v = [Box::new(1), Box::new(3), Box::new(3), Box::new(7)];
+        assert_eq!(v.lower_bound_deref(&3), Ok(1));
+        assert_eq!(v.upper_bound_deref(&3), Ok(3));
+        assert_eq!(v.lower_bound_deref(&0), Ok(0));
+        assert_eq!(v.lower_bound_deref(&8), Err(4));
+
+        let v: Vec<Rc<str>> = vec!["a".into(), "b".into(), "d".into()];
+        assert_eq!(v.lower_bound_deref("c"), Ok(2));
+
+        let words = ["x", "y"].map(String::from);
+        let refs = words.iter().collect::<Vec<_>>();
+        assert_eq!(refs.upper_bound_deref(&"x".to_string()), Ok(1));
+    }
+}
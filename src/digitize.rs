@@ -0,0 +1,79 @@
+/// Bin indices of `values` against the sorted bin `edges`, as NumPy's `digitize`.
+///
+/// With `right_closed == false` bins are `edges[i - 1] <= x < edges[i]`, so the index of `x` is
+/// its `upper_bound` in `edges`; with `right_closed == true` they are `edges[i - 1] < x <= edges[i]`
+/// and the index is its `lower_bound`. Values below the first edge get `0`, values past the last
+/// get `edges.len()`, and so do incomparable values such as NaN, which NumPy also sorts last.
+/// ```
+/// # use bound_stl::digitize;
+/// let edges = [0.0, 1.0, 2.5, 4.0];
+/// let values = [-1.0, 0.0, 0.5, 2.5, 3.9, 4.0, 10.0, f64::NAN];
+/// assert_eq!(digitize(&values, &edges, false), [0, 1, 1, 3, 3, 4, 4, 4]);
+/// assert_eq!(digitize(&values, &edges, true), [0, 0, 1, 2, 3, 3, 4, 4]);
+/// ```
+pub fn digitize<T: PartialOrd>(values: &[T], edges: &[T], right_closed: bool) -> Vec<usize> {
+    let mut out = vec![0; values.len()];
+    digitize_into(values, edges, right_closed, &mut out);
+    out
+}
+
+/// [`digitize`] into a caller-provided buffer, which must be as long as `values`.
+///
+/// Sorted input is merged against the edges in one linear pass;
+/// anything else is binary searched value by value.
+pub fn digitize_into<T: PartialOrd>(values: &[T], edges: &[T], right_closed: bool, out: &mut [usize]) {
+    assert_eq!(
+        values.len(),
+        out.len(),
+        "output length differs from the number of values"
+    );
+    debug_assert!(edges.is_sorted_by(|a, b| a <= b), "edges are not sorted");
+    // whether `x` falls after edge `e`
+    let after = |e: &T, x: &T| if right_closed { e < x } else { e <= x };
+    let incomparable = |x: &T| x.partial_cmp(x).is_none();
+    // a lone incomparable value counts as sorted, so the merge checks for them too
+    if values.is_sorted_by(|a, b| a <= b) {
+        let mut bin = 0;
+        for (x, o) in values.iter().zip(out.iter_mut()) {
+            while bin < edges.len() && after(&edges[bin], x) {
+                bin += 1;
+            }
+            *o = if incomparable(x) { edges.len() } else { bin };
+        }
+    } else {
+        for (x, o) in values.iter().zip(out.iter_mut()) {
+            *o = if incomparable(x) {
+                edges.len()
+            } else {
+                edges.partition_point(|e| after(e, x))
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::{digitize, digitize_into, LowerBound, UpperBound};
+
+    #[test]
+    fn test_digitize() {
+        let edges = [2, 3, 3, 7, 11];
+        let unsorted = [5, 0, 11, 3, 12, 2, 7, 3, -4];
+        let mut sorted = unsorted;
+        sorted.sort();
+        for values in [unsorted, sorted] {
+            let pos = |r: Result<usize, usize>| r.unwrap_or_else(|e| e);
+            let left = values.iter().map(|x| pos(edges.upper_bound(x))).collect::<Vec<_>>();
+            let right = values.iter().map(|x| pos(edges.lower_bound(x))).collect::<Vec<_>>();
+            assert_eq!(digitize(&values, &edges, false), left);
+            assert_eq!(digitize(&values, &edges, true), right);
+        }
+        assert_eq!(digitize(&[1, 2], &[], false), [0, 0]);
+
+        let mut out = [9; 4];
+        digitize_into(&[0.5, f64::NAN, -1.0, 1.0], &[0.0, 1.0], true, &mut out);
+        assert_eq!(out, [1, 2, 0, 1]);
+        assert_eq!(digitize(&[f64::NAN], &[0.0, 1.0], false), [2]);
+        assert_eq!(digitize(&[f64::NAN, 0.5], &[0.0, 1.0], false), [2, 1]);
+    }
+}
@@ -0,0 +1,189 @@
+use crate::lower_bound_index;
+
+/// Map-style entries into a `Vec<(K, V)>` kept sorted by unique keys, a flat map.
+///
+/// `entry` does one lower bound search; the returned entry remembers the position, so
+/// inserting through it or modifying the value does not search again.
+/// ```
+/// # use bound_stl::SortedEntry;
+/// let mut counts: Vec<(&str, u32)> = Vec::new();
+/// for word in ["b", "a", "b", "c", "b"] {
+///     counts.entry(word).and_modify(|n| *n += 1).or_insert(1);
+/// }
+/// assert_eq!(counts, [("a", 1), ("b", 3), ("c", 1)]);
+/// ```
+pub trait SortedEntry<K, V> {
+    fn entry(&mut self, key: K) -> Entry<'_, K, V>;
+}
+
+impl<K: Ord, V> SortedEntry<K, V> for Vec<(K, V)> {
+    fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+        match lower_bound_index(self.len(), |i| self[i].0.cmp(&key)) {
+            Ok(index) if self[index].0 == key => Entry::Occupied(OccupiedEntry { vec: self, index }),
+            pos => Entry::Vacant(VacantEntry {
+                vec: self,
+                index: pos.unwrap_or_else(|e| e),
+                key,
+            }),
+        }
+    }
+}
+
+/// A view into one key of a flat map, from [`SortedEntry::entry`].
+#[derive(Debug)]
+pub enum Entry<'a, K, V> {
+    Occupied(OccupiedEntry<'a, K, V>),
+    Vacant(VacantEntry<'a, K, V>),
+}
+
+/// An entry whose key is present, at `index`.
+#[derive(Debug)]
+pub struct OccupiedEntry<'a, K, V> {
+    vec: &'a mut Vec<(K, V)>,
+    index: usize,
+}
+
+/// An entry whose key is absent and would be inserted at `index`.
+#[derive(Debug)]
+pub struct VacantEntry<'a, K, V> {
+    vec: &'a mut Vec<(K, V)>,
+    index: usize,
+    key: K,
+}
+
+impl<'a, K, V> Entry<'a, K, V> {
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(e) => e.key(),
+            Entry::Vacant(e) => e.key(),
+        }
+    }
+
+    /// Position of the key in the vector, present or not.
+    pub fn index(&self) -> usize {
+        match self {
+            Entry::Occupied(e) => e.index,
+            Entry::Vacant(e) => e.index,
+        }
+    }
+
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => e.insert(default()),
+        }
+    }
+
+    pub fn or_insert_with_key<F: FnOnce(&K) -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => {
+                let value = default(&e.key);
+                e.insert(value)
+            }
+        }
+    }
+
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(e) = &mut self {
+            f(e.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, K, V> OccupiedEntry<'a, K, V> {
+    pub fn key(&self) -> &K {
+        &self.vec[self.index].0
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn get(&self) -> &V {
+        &self.vec[self.index].1
+    }
+
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.vec[self.index].1
+    }
+
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.vec[self.index].1
+    }
+
+    /// Replaces the value, returning the old one.
+    pub fn insert(&mut self, value: V) -> V {
+        std::mem::replace(self.get_mut(), value)
+    }
+
+    pub fn remove_entry(self) -> (K, V) {
+        self.vec.remove(self.index)
+    }
+
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+}
+
+impl<'a, K, V> VacantEntry<'a, K, V> {
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// Inserts the pair at its sorted position.
+    pub fn insert(self, value: V) -> &'a mut V {
+        self.vec.insert(self.index, (self.key, value));
+        &mut self.vec[self.index].1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::{Entry, SortedEntry};
+
+    #[test]
+    fn test_sorted_entry() {
+        let mut map = vec![(2, "b"), (4, "d")];
+        match map.entry(3) {
+            Entry::Vacant(e) => {
+                assert_eq!(e.index(), 1);
+                assert_eq!(*e.insert("c"), "c");
+            }
+            Entry::Occupied(_) => unreachable!(),
+        }
+        assert_eq!(*map.entry(4).or_insert("x"), "d");
+        assert_eq!(*map.entry(9).or_insert_with_key(|_| "i"), "i");
+        assert_eq!(*map.entry(0).or_default(), "");
+        map.entry(2).and_modify(|v| *v = "B").or_insert("x");
+        assert_eq!(map, [(0, ""), (2, "B"), (3, "c"), (4, "d"), (9, "i")]);
+
+        if let Entry::Occupied(mut e) = map.entry(4) {
+            assert_eq!(e.insert("D"), "d");
+            assert_eq!(e.remove_entry(), (4, "D"));
+        }
+        assert_eq!(map.entry(4).index(), 3);
+        assert_eq!(map.entry(10).key(), &10);
+        assert_eq!(map.len(), 4);
+    }
+}
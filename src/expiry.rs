@@ -0,0 +1,126 @@
+use crate::{SecondaryIndex, UpperBound};
+
+/// Keys ordered by their expiration time, for TTL eviction loops.
+///
+/// Each key has one deadline of type `T`, usually an `Instant` or a timestamp. Rescheduling a
+/// key moves it, `next_deadline` is the earliest entry, and `drain_expired(&now)` removes and
+/// returns every key whose deadline is not after `now`, earliest first, with one bound query.
+/// ```
+/// # use bound_stl::ExpiryIndex;
+/// let mut ttl = ExpiryIndex::new();
+/// ttl.insert("session-a", 30);
+/// ttl.insert("session-b", 10);
+/// ttl.insert("session-c", 20);
+/// assert_eq!(ttl.next_deadline(), Some((&10, &"session-b")));
+///
+/// ttl.reschedule(&"session-b", 40); // touched, extend its lifetime
+/// assert_eq!(ttl.drain_expired(&30), vec![("session-c", 20), ("session-a", 30)]);
+/// assert_eq!(ttl.next_deadline(), Some((&40, &"session-b")));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ExpiryIndex<K, T> {
+    index: SecondaryIndex<K, T>,
+}
+
+impl<K: Ord + Clone, T: Ord + Clone> Default for ExpiryIndex<K, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord + Clone, T: Ord + Clone> ExpiryIndex<K, T> {
+    pub fn new() -> Self {
+        ExpiryIndex {
+            index: SecondaryIndex::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.index.clear();
+    }
+
+    /// Schedules `key` to expire at `deadline`, returning its previous deadline.
+    pub fn insert(&mut self, key: K, deadline: T) -> Option<T> {
+        self.index.insert(key, deadline)
+    }
+
+    /// Moves the deadline of a scheduled key, returning the old one;
+    /// does nothing and returns `None` if `key` is not scheduled.
+    pub fn reschedule(&mut self, key: &K, deadline: T) -> Option<T> {
+        self.index.get(key)?;
+        self.index.insert(key.clone(), deadline)
+    }
+
+    /// Unschedules `key`, returning its deadline.
+    pub fn remove(&mut self, key: &K) -> Option<T> {
+        self.index.remove(key)
+    }
+
+    pub fn deadline(&self, key: &K) -> Option<&T> {
+        self.index.get(key)
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.index.get(key).is_some()
+    }
+
+    /// The earliest deadline and its key.
+    pub fn next_deadline(&self) -> Option<(&T, &K)> {
+        self.index.get_index(0)
+    }
+
+    /// Number of keys whose deadline is not after `now`.
+    pub fn count_expired(&self, now: &T) -> usize {
+        self.index.upper_bound(now).unwrap_or_else(|e| e)
+    }
+
+    /// Removes the keys whose deadline is not after `now` and returns them, earliest first.
+    pub fn drain_expired(&mut self, now: &T) -> Vec<(K, T)> {
+        let end = self.count_expired(now);
+        self.index.drain_front(end).into_iter().map(|(t, k)| (k, t)).collect()
+    }
+
+    /// Scheduled keys, earliest deadline first.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&T, &K)> + ExactSizeIterator {
+        self.index.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::ExpiryIndex;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_expiry_index() {
+        let start = Instant::now();
+        let at = |s: u64| start + Duration::from_secs(s);
+        let mut ttl = ExpiryIndex::new();
+        for (k, s) in [(1, 5), (2, 3), (3, 8), (4, 3), (5, 1)] {
+            assert_eq!(ttl.insert(k, at(s)), None);
+        }
+        assert_eq!(ttl.next_deadline(), Some((&at(1), &5)));
+        assert_eq!(ttl.reschedule(&5, at(9)), Some(at(1)));
+        assert_eq!(ttl.reschedule(&6, at(0)), None);
+        assert!(!ttl.contains_key(&6));
+        assert_eq!(ttl.count_expired(&at(2)), 0);
+        assert_eq!(ttl.count_expired(&at(5)), 3);
+
+        let expired = ttl.drain_expired(&at(5));
+        assert_eq!(expired, vec![(2, at(3)), (4, at(3)), (1, at(5))]);
+        assert_eq!(ttl.deadline(&1), None);
+        assert_eq!(ttl.len(), 2);
+        assert!(ttl.drain_expired(&at(5)).is_empty());
+        assert_eq!(ttl.remove(&3), Some(at(8)));
+        assert_eq!(ttl.drain_expired(&at(100)), vec![(5, at(9))]);
+        assert!(ttl.is_empty());
+    }
+}
@@ -0,0 +1,149 @@
+use crate::{lower_bound_index, upper_bound_index};
+use std::cmp::Ordering;
+use std::fmt;
+
+/// One comparison made by a binary search: the element at `index` compared as `ordering`
+/// against the target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Probe {
+    pub index: usize,
+    pub ordering: Ordering,
+}
+
+/// The trace of a bound search, returned by the [`ExplainBound`] methods.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explain {
+    /// The probes in the order they were made.
+    pub probes: Vec<Probe>,
+    /// What the plain bound method returns.
+    pub result: Result<usize, usize>,
+}
+
+impl Explain {
+    /// A pair of probes whose outcomes can't both hold on sorted data: the one at the lower
+    /// index compared greater than the one at the higher index.
+    /// Such a pair means the data is not sorted by the comparator, or the comparator is not a
+    /// consistent total order, and the result should not be trusted.
+    pub fn first_inconsistency(&self) -> Option<(Probe, Probe)> {
+        let mut sorted = self.probes.clone();
+        sorted.sort_by_key(|p| p.index);
+        sorted
+            .windows(2)
+            .find(|w| w[0].ordering > w[1].ordering)
+            .map(|w| (w[0], w[1]))
+    }
+}
+
+impl fmt::Display for Explain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (step, p) in self.probes.iter().enumerate() {
+            writeln!(f, "step {step}: [{}] is {:?}", p.index, p.ordering)?;
+        }
+        write!(f, "result: {:?}", self.result)?;
+        if let Some((a, b)) = self.first_inconsistency() {
+            write!(
+                f,
+                " (inconsistent: [{}] is {:?} but [{}] is {:?})",
+                a.index, a.ordering, b.index, b.ordering
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Bound searches that also report every probe they made, for debugging wrong answers caused
+/// by unsorted data or a comparator that disagrees with the sort order.
+/// The results are the same as those of `LowerBound` and `UpperBound`.
+/// ```
+/// # use bound_stl::ExplainBound;
+/// let v = vec![1, 3, 5, 7, 9];
+/// let e = v.lower_bound_explain(&6);
+/// assert_eq!(e.result, Ok(3));
+/// assert_eq!(e.probes.iter().map(|p| p.index).collect::<Vec<_>>(), vec![2, 4, 3]);
+/// assert!(e.first_inconsistency().is_none());
+///
+/// // not sorted: the probes give it away
+/// let v = vec![1, 8, 5, 7, 2];
+/// let e = v.lower_bound_explain(&5);
+/// assert!(e.first_inconsistency().is_some());
+/// ```
+pub trait ExplainBound {
+    type Item;
+
+    fn lower_bound_explain(&self, x: &Self::Item) -> Explain;
+
+    fn lower_bound_explain_by<'a, F>(&'a self, f: F) -> Explain
+    where
+        F: FnMut(&'a Self::Item) -> Ordering;
+
+    fn upper_bound_explain(&self, x: &Self::Item) -> Explain;
+
+    fn upper_bound_explain_by<'a, F>(&'a self, f: F) -> Explain
+    where
+        F: FnMut(&'a Self::Item) -> Ordering;
+}
+
+impl<T: Ord> ExplainBound for [T] {
+    type Item = T;
+
+    fn lower_bound_explain(&self, x: &T) -> Explain {
+        self.lower_bound_explain_by(|y| y.cmp(x))
+    }
+
+    fn lower_bound_explain_by<'a, F>(&'a self, mut f: F) -> Explain
+    where
+        F: FnMut(&'a T) -> Ordering,
+    {
+        let mut probes = Vec::new();
+        let result = lower_bound_index(self.len(), |index| {
+            let ordering = f(&self[index]);
+            probes.push(Probe { index, ordering });
+            ordering
+        });
+        Explain { probes, result }
+    }
+
+    fn upper_bound_explain(&self, x: &T) -> Explain {
+        self.upper_bound_explain_by(|y| y.cmp(x))
+    }
+
+    fn upper_bound_explain_by<'a, F>(&'a self, mut f: F) -> Explain
+    where
+        F: FnMut(&'a T) -> Ordering,
+    {
+        let mut probes = Vec::new();
+        let result = upper_bound_index(self.len(), |index| {
+            let ordering = f(&self[index]);
+            probes.push(Probe { index, ordering });
+            ordering
+        });
+        Explain { probes, result }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::{ExplainBound, LowerBound, UpperBound};
+
+    #[test]
+    fn test_explain() {
+        let v = (0..100).map(|x| x * 2).collect::<Vec<i32>>();
+        for x in -1..202 {
+            let e = v.lower_bound_explain(&x);
+            assert_eq!(e.result, v.lower_bound(&x));
+            assert!(e.probes.len() <= 7);
+            assert!(e.first_inconsistency().is_none());
+            assert_eq!(v.upper_bound_explain(&x).result, v.upper_bound(&x));
+        }
+
+        let e = Vec::<i32>::new().upper_bound_explain(&3);
+        assert!(e.probes.is_empty());
+        assert_eq!(e.to_string(), "result: Err(0)");
+
+        // a comparator that disagrees with the sort order
+        let e = v.lower_bound_explain_by(|y| 100.cmp(y));
+        let (a, b) = e.first_inconsistency().unwrap();
+        assert!(a.index < b.index && a.ordering > b.ordering);
+        assert!(e.to_string().contains("inconsistent"));
+    }
+}
@@ -0,0 +1,505 @@
+//! External-memory sorting into searchable files.
+//!
+//! [`ExternalSorter`] sorts inputs larger than memory: it cuts the input into sorted runs on
+//! disk, merges them `fan_in` at a time and writes one sorted file, which [`SortedFile`] then
+//! searches in place with a binary search over record positions.
+//!
+//! Records are fixed-size ([`FixedRecord`]), so record `i` lives at byte `i * SIZE` and the
+//! sorted file needs no index of its own. Variable-length keys can be stored as fixed-width
+//! byte arrays, e.g. padded [`keyenc`](crate::keyenc) encodings.
+//!
+//! ```
+//! use bound_stl::extsort::ExternalSorter;
+//!
+//! let dir = std::env::temp_dir().join(format!("extsort-doc-{}", std::process::id()));
+//! std::fs::create_dir_all(&dir)?;
+//! let input = (0..10_000u32).rev().map(|i| i % 1000);
+//! let sorted = ExternalSorter::new(&dir).run_size(1024).sort(input, dir.join("out.bin"))?;
+//! assert_eq!(sorted.len(), 10_000);
+//! assert_eq!(sorted.lower_bound(&500)?, Ok(5000));
+//! assert_eq!(sorted.get(9_999)?, 999);
+//! # std::fs::remove_dir_all(&dir)?;
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use crate::trace::{debug_event, debug_span};
+use crate::{lower_bound_index, upper_bound_index};
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::marker::PhantomData;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+use std::sync::Mutex;
+
+/// A record with a fixed-size binary form.
+pub trait FixedRecord: Sized {
+    const SIZE: usize;
+    /// Writes the record into `buf`, which is exactly `SIZE` bytes long.
+    fn write_to(&self, buf: &mut [u8]);
+    /// Reads a record from `buf`, which is exactly `SIZE` bytes long.
+    fn read_from(buf: &[u8]) -> Self;
+}
+
+macro_rules! impl_fixed_record {
+    ($($t:ty),*) => {$(
+        impl FixedRecord for $t {
+            const SIZE: usize = std::mem::size_of::<$t>();
+            fn write_to(&self, buf: &mut [u8]) {
+                buf.copy_from_slice(&self.to_le_bytes());
+            }
+            fn read_from(buf: &[u8]) -> Self {
+                <$t>::from_le_bytes(buf.try_into().unwrap())
+            }
+        }
+    )*};
+}
+
+impl_fixed_record!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+impl<const N: usize> FixedRecord for [u8; N] {
+    const SIZE: usize = N;
+    fn write_to(&self, buf: &mut [u8]) {
+        buf.copy_from_slice(self);
+    }
+    fn read_from(buf: &[u8]) -> Self {
+        buf.try_into().unwrap()
+    }
+}
+
+/// A key with a payload, sorted by key first.
+impl<A: FixedRecord, B: FixedRecord> FixedRecord for (A, B) {
+    const SIZE: usize = A::SIZE + B::SIZE;
+    fn write_to(&self, buf: &mut [u8]) {
+        self.0.write_to(&mut buf[..A::SIZE]);
+        self.1.write_to(&mut buf[A::SIZE..]);
+    }
+    fn read_from(buf: &[u8]) -> Self {
+        (A::read_from(&buf[..A::SIZE]), B::read_from(&buf[A::SIZE..]))
+    }
+}
+
+/// Sorts record streams larger than memory, see the [module documentation](self).
+#[derive(Debug, Clone)]
+pub struct ExternalSorter {
+    temp_dir: PathBuf,
+    run_size: usize,
+    fan_in: usize,
+}
+
+impl ExternalSorter {
+    /// A sorter keeping its temporary runs in `temp_dir`, which must exist.
+    pub fn new<P: AsRef<Path>>(temp_dir: P) -> Self {
+        ExternalSorter {
+            temp_dir: temp_dir.as_ref().to_path_buf(),
+            run_size: 1 << 20,
+            fan_in: 64,
+        }
+    }
+
+    /// Records sorted in memory per run; this bounds the memory used. Defaults to 2^20.
+    pub fn run_size(mut self, records: usize) -> Self {
+        assert!(records > 0, "run size must not be zero");
+        self.run_size = records;
+        self
+    }
+
+    /// Runs merged at once; more runs are merged in several passes. Defaults to 64.
+    pub fn fan_in(mut self, runs: usize) -> Self {
+        assert!(runs >= 2, "fan-in must be at least 2");
+        self.fan_in = runs;
+        self
+    }
+
+    /// Sorts `input` into the file `output` and opens it for searching.
+    pub fn sort<T, I, P>(&self, input: I, output: P) -> io::Result<SortedFile<T>>
+    where
+        T: FixedRecord + Ord,
+        I: IntoIterator<Item = T>,
+        P: AsRef<Path>,
+    {
+        let _span = debug_span!("extsort.sort", run_size = self.run_size, fan_in = self.fan_in);
+        let mut runs = TempRuns { paths: Vec::new() };
+        let mut input = input.into_iter().peekable();
+        let mut chunk = Vec::with_capacity(self.run_size.min(1 << 20));
+        let mut records = 0;
+        while input.peek().is_some() {
+            chunk.extend(input.by_ref().take(self.run_size));
+            chunk.sort_unstable();
+            let path = self.temp_path();
+            runs.paths.push(path.clone());
+            records += write_records(&path, chunk.drain(..).map(Ok))?;
+        }
+        debug_event!("runs written", runs = runs.paths.len(), records = records);
+
+        while runs.paths.len() > self.fan_in {
+            let path = self.temp_path();
+            runs.paths.push(path.clone());
+            merge_runs::<T>(&runs.paths[..self.fan_in], &path)?;
+            // each run leaves `runs` only once deleted, so on failure `TempRuns` removes the rest
+            for _ in 0..self.fan_in {
+                fs::remove_file(&runs.paths[0])?;
+                runs.paths.remove(0);
+            }
+        }
+        merge_runs::<T>(&runs.paths, output.as_ref())?;
+        SortedFile::open(output)
+    }
+
+    fn temp_path(&self) -> PathBuf {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let n = NEXT.fetch_add(1, AtomicOrdering::Relaxed);
+        self.temp_dir
+            .join(format!("bound-stl-run-{}-{n}.tmp", std::process::id()))
+    }
+}
+
+/// Removes whatever runs are left, also when sorting fails half-way.
+struct TempRuns {
+    paths: Vec<PathBuf>,
+}
+
+impl Drop for TempRuns {
+    fn drop(&mut self) {
+        for p in &self.paths {
+            let _ = fs::remove_file(p);
+        }
+    }
+}
+
+/// Returns the number of records written.
+fn write_records<T, I>(path: &Path, records: I) -> io::Result<usize>
+where
+    T: FixedRecord,
+    I: Iterator<Item = io::Result<T>>,
+{
+    let mut out = BufWriter::new(File::create(path)?);
+    let mut buf = vec![0; T::SIZE];
+    let mut written = 0;
+    for r in records {
+        r?.write_to(&mut buf);
+        out.write_all(&buf)?;
+        written += 1;
+    }
+    out.flush()?;
+    Ok(written)
+}
+
+fn merge_runs<T: FixedRecord + Ord>(inputs: &[PathBuf], output: &Path) -> io::Result<()> {
+    let _span = debug_span!("extsort.merge", fan_in = inputs.len());
+    let mut readers = inputs
+        .iter()
+        .map(|p| File::open(p).map(|f| RecordReader::<T, _>::new(BufReader::new(f))))
+        .collect::<io::Result<Vec<_>>>()?;
+    let mut heap = BinaryHeap::with_capacity(readers.len());
+    for (i, r) in readers.iter_mut().enumerate() {
+        if let Some(rec) = r.next().transpose()? {
+            heap.push(Reverse((rec, i)));
+        }
+    }
+    let merged = std::iter::from_fn(|| {
+        let Reverse((rec, i)) = heap.pop()?;
+        match readers[i].next().transpose() {
+            Ok(Some(next)) => heap.push(Reverse((next, i))),
+            Ok(None) => {}
+            Err(e) => return Some(Err(e)),
+        }
+        Some(Ok(rec))
+    });
+    let records = write_records(output, merged)?;
+    debug_event!("merged", records = records, bytes_written = records * T::SIZE);
+    Ok(())
+}
+
+/// Reads consecutive records from a byte stream.
+struct RecordReader<T, R> {
+    inner: R,
+    buf: Vec<u8>,
+    _marker: PhantomData<T>,
+}
+
+impl<T: FixedRecord, R: Read> RecordReader<T, R> {
+    fn new(inner: R) -> Self {
+        RecordReader {
+            inner,
+            buf: vec![0; T::SIZE],
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: FixedRecord, R: Read> Iterator for RecordReader<T, R> {
+    type Item = io::Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut filled = 0;
+        while filled < self.buf.len() {
+            match self.inner.read(&mut self.buf[filled..]) {
+                Ok(0) if filled == 0 => return None,
+                Ok(0) => return Some(Err(io::Error::new(io::ErrorKind::InvalidData, "truncated record"))),
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        Some(Ok(T::read_from(&self.buf)))
+    }
+}
+
+/// A sorted file of fixed-size records, searched in place.
+///
+/// Each probe of a bound search reads one record, so a search costs `O(log n)` reads
+/// without loading the file. The bound methods follow the `LowerBound` / `UpperBound`
+/// `Result` convention, wrapped in an `io::Result` for read errors.
+#[derive(Debug)]
+pub struct SortedFile<T> {
+    file: Mutex<File>,
+    len: usize,
+    _marker: PhantomData<T>,
+}
+
+impl<T: FixedRecord> SortedFile<T> {
+    /// Opens a file written by [`ExternalSorter::sort`], or any file of sorted records.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let bytes = file.metadata()?.len() as usize;
+        if T::SIZE == 0 || !bytes.is_multiple_of(T::SIZE) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "file is not a whole number of records",
+            ));
+        }
+        Ok(SortedFile {
+            file: Mutex::new(file),
+            len: bytes / T::SIZE,
+            _marker: PhantomData,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Reads record `idx`.
+    pub fn get(&self, idx: usize) -> io::Result<T> {
+        if idx >= self.len {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "record index out of bounds",
+            ));
+        }
+        let mut buf = vec![0; T::SIZE];
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        file.seek(SeekFrom::Start((idx * T::SIZE) as u64))?;
+        file.read_exact(&mut buf)?;
+        Ok(T::read_from(&buf))
+    }
+
+    /// Reads the records at positions `range`, in order.
+    pub fn read_range(&self, range: Range<usize>) -> io::Result<Vec<T>> {
+        let range = range.start.min(self.len)..range.end.min(self.len);
+        let mut buf = vec![0; range.len() * T::SIZE];
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        file.seek(SeekFrom::Start((range.start * T::SIZE) as u64))?;
+        file.read_exact(&mut buf)?;
+        debug_event!(
+            "sorted_file.read_range",
+            records = range.len(),
+            seeks = 1,
+            bytes_read = buf.len()
+        );
+        Ok(buf.chunks_exact(T::SIZE.max(1)).map(T::read_from).collect())
+    }
+
+    pub fn lower_bound(&self, x: &T) -> io::Result<Result<usize, usize>>
+    where
+        T: Ord,
+    {
+        self.lower_bound_by(|y| y.cmp(x))
+    }
+
+    pub fn lower_bound_by<F>(&self, mut f: F) -> io::Result<Result<usize, usize>>
+    where
+        F: FnMut(&T) -> Ordering,
+    {
+        let (mut err, mut probes) = (None, 0);
+        let res = lower_bound_index(self.len, |i| {
+            probes += 1;
+            self.probe(i, &mut f, &mut err)
+        });
+        self.trace_search("sorted_file.lower_bound", probes, &res);
+        err.map_or(Ok(res), Err)
+    }
+
+    pub fn lower_bound_by_key<K, F>(&self, k: &K, mut f: F) -> io::Result<Result<usize, usize>>
+    where
+        F: FnMut(&T) -> K,
+        K: Ord,
+    {
+        self.lower_bound_by(|y| f(y).cmp(k))
+    }
+
+    pub fn upper_bound(&self, x: &T) -> io::Result<Result<usize, usize>>
+    where
+        T: Ord,
+    {
+        self.upper_bound_by(|y| y.cmp(x))
+    }
+
+    pub fn upper_bound_by<F>(&self, mut f: F) -> io::Result<Result<usize, usize>>
+    where
+        F: FnMut(&T) -> Ordering,
+    {
+        let (mut err, mut probes) = (None, 0);
+        let res = upper_bound_index(self.len, |i| {
+            probes += 1;
+            self.probe(i, &mut f, &mut err)
+        });
+        self.trace_search("sorted_file.upper_bound", probes, &res);
+        err.map_or(Ok(res), Err)
+    }
+
+    pub fn upper_bound_by_key<K, F>(&self, k: &K, mut f: F) -> io::Result<Result<usize, usize>>
+    where
+        F: FnMut(&T) -> K,
+        K: Ord,
+    {
+        self.upper_bound_by(|y| f(y).cmp(k))
+    }
+
+    /// Every probe seeks and reads one record.
+    fn trace_search(&self, search: &'static str, probes: usize, res: &Result<usize, usize>) {
+        let position = res.unwrap_or_else(|e| e);
+        debug_event!(
+            "search",
+            search = search,
+            len = self.len,
+            probes = probes,
+            seeks = probes,
+            bytes_read = probes * T::SIZE,
+            position = position,
+        );
+    }
+
+    /// Compares record `i`; after a read error the search is steered to an end quickly and
+    /// the error is reported instead of the result.
+    fn probe<F>(&self, i: usize, f: &mut F, err: &mut Option<io::Error>) -> Ordering
+    where
+        F: FnMut(&T) -> Ordering,
+    {
+        if err.is_some() {
+            return Ordering::Greater;
+        }
+        match self.get(i) {
+            Ok(rec) => f(&rec),
+            Err(e) => {
+                *err = Some(e);
+                Ordering::Greater
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::extsort::{ExternalSorter, SortedFile};
+    use bound_stl::{LowerBound, UpperBound};
+
+    #[test]
+    fn test_external_sort() {
+        let dir = std::env::temp_dir().join(format!("bound-stl-extsort-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut seed = 1u64;
+        let input = (0..5000)
+            .map(|i| {
+                seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                ((seed >> 40) as u32 % 700, i as u16)
+            })
+            .collect::<Vec<_>>();
+        // 5000 / 100 = 50 runs merged 4 at a time: several merge passes
+        let out = dir.join("pairs.bin");
+        let sorted = ExternalSorter::new(&dir)
+            .run_size(100)
+            .fan_in(4)
+            .sort(input.clone(), &out)
+            .unwrap();
+        let mut expected = input;
+        expected.sort_unstable();
+        assert_eq!(sorted.read_range(0..sorted.len()).unwrap(), expected);
+
+        let keys = expected.iter().map(|p| p.0).collect::<Vec<_>>();
+        for k in [0, 1, 350, 699, 700] {
+            assert_eq!(sorted.lower_bound_by_key(&k, |p| p.0).unwrap(), keys.lower_bound(&k));
+            assert_eq!(sorted.upper_bound_by_key(&k, |p| p.0).unwrap(), keys.upper_bound(&k));
+        }
+        assert!(sorted.get(5000).is_err());
+        let leftovers = std::fs::read_dir(&dir).unwrap().count();
+        assert_eq!(leftovers, 1, "temporary runs should be removed");
+
+        let empty = ExternalSorter::new(&dir)
+            .sort(Vec::<u64>::new(), dir.join("empty.bin"))
+            .unwrap();
+        assert!(empty.is_empty());
+        assert_eq!(empty.lower_bound(&3).unwrap(), Err(0));
+
+        std::fs::write(dir.join("ragged.bin"), [0u8; 7]).unwrap();
+        assert!(SortedFile::<u32>::open(dir.join("ragged.bin")).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_sorted_file_tracing() {
+        use std::sync::{Arc, Mutex};
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata};
+
+        /// Collects the `probes` field of every event.
+        #[derive(Clone, Default)]
+        struct Probes(Arc<Mutex<Vec<u64>>>);
+
+        impl Visit for Probes {
+            fn record_u64(&mut self, field: &Field, value: u64) {
+                if field.name() == "probes" {
+                    self.0.lock().unwrap().push(value);
+                }
+            }
+
+            fn record_debug(&mut self, _: &Field, _: &dyn std::fmt::Debug) {}
+        }
+
+        impl tracing::Subscriber for Probes {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, _: &Attributes<'_>) -> Id {
+                Id::from_u64(1)
+            }
+            fn record(&self, _: &Id, _: &Record<'_>) {}
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+            fn event(&self, event: &Event<'_>) {
+                event.record(&mut self.clone());
+            }
+            fn enter(&self, _: &Id) {}
+            fn exit(&self, _: &Id) {}
+        }
+
+        let path = std::env::temp_dir().join(format!("bound-stl-tracing-{}.bin", std::process::id()));
+        std::fs::write(&path, (0..1024u32).flat_map(u32::to_le_bytes).collect::<Vec<_>>()).unwrap();
+        let sorted = SortedFile::<u32>::open(&path).unwrap();
+        let probes = Probes::default();
+        tracing::subscriber::with_default(probes.clone(), || {
+            assert_eq!(sorted.lower_bound(&100).unwrap(), Ok(100));
+        });
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(*probes.0.lock().unwrap(), [10]);
+    }
+}
@@ -0,0 +1,330 @@
+use crate::{lower_bound_index, range_index, upper_bound_index, LowerBound, UpperBound};
+use std::cmp::Ordering;
+use std::ops::{Range, RangeBounds};
+
+/// A map from keys to any number of values, stored as key-sorted pairs in one `Vec`.
+///
+/// Pairs with equal keys sit next to each other in insertion order, so all values of a key
+/// are one contiguous slice, found with a `lower_bound` / `upper_bound` pair.
+/// With the `rkyv` feature the map is archivable, and its archived form answers the same
+/// queries on the archive bytes, without deserializing.
+/// ```
+/// # use bound_stl::FlatMultiMap;
+/// let mut edges = FlatMultiMap::new();
+/// edges.insert(1, 'b');
+/// edges.insert(0, 'a');
+/// edges.insert(1, 'c');
+/// assert_eq!(edges.get_all(&1), &[(1, 'b'), (1, 'c')]);
+/// assert_eq!(edges.get_all(&2), &[]);
+///
+/// let groups = edges.groups().map(|(k, g)| (*k, g.len())).collect::<Vec<_>>();
+/// assert_eq!(groups, vec![(0, 1), (1, 2)]);
+/// assert_eq!(edges.remove_all(&1), vec![(1, 'b'), (1, 'c')]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "rkyv", derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize))]
+pub struct FlatMultiMap<K, V> {
+    entries: Vec<(K, V)>,
+}
+
+impl<K, V> Default for FlatMultiMap<K, V> {
+    fn default() -> Self {
+        FlatMultiMap { entries: Vec::new() }
+    }
+}
+
+impl<K: Ord, V> FlatMultiMap<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        FlatMultiMap {
+            entries: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// All pairs, sorted by key.
+    pub fn as_slice(&self) -> &[(K, V)] {
+        &self.entries
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, (K, V)> {
+        self.entries.iter()
+    }
+
+    /// Adds a pair after the existing pairs with the same key and returns its index.
+    pub fn insert(&mut self, key: K, value: V) -> usize {
+        let pos = upper_bound_index(self.len(), |i| self.entries[i].0.cmp(&key)).unwrap_or_else(|e| e);
+        self.entries.insert(pos, (key, value));
+        pos
+    }
+
+    /// Index range of the pairs with key `key`.
+    pub fn equal_range(&self, key: &K) -> Range<usize> {
+        let lo = lower_bound_index(self.len(), |i| self.entries[i].0.cmp(key)).unwrap_or_else(|e| e);
+        let hi = upper_bound_index(self.len(), |i| self.entries[i].0.cmp(key)).unwrap_or_else(|e| e);
+        lo..hi
+    }
+
+    /// The pairs with key `key`, in insertion order.
+    pub fn get_all(&self, key: &K) -> &[(K, V)] {
+        &self.entries[self.equal_range(key)]
+    }
+
+    /// The values of key `key`, mutably.
+    pub fn values_mut(&mut self, key: &K) -> impl Iterator<Item = &mut V> {
+        let range = self.equal_range(key);
+        self.entries[range].iter_mut().map(|(_, v)| v)
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        !self.equal_range(key).is_empty()
+    }
+
+    pub fn count(&self, key: &K) -> usize {
+        self.equal_range(key).len()
+    }
+
+    /// Removes and returns all pairs with key `key`.
+    pub fn remove_all(&mut self, key: &K) -> Vec<(K, V)> {
+        let range = self.equal_range(key);
+        self.entries.drain(range).collect()
+    }
+
+    /// Keeps only the pairs for which `f` returns `true`.
+    pub fn retain<F: FnMut(&K, &mut V) -> bool>(&mut self, mut f: F) {
+        self.entries.retain_mut(|(k, v)| f(k, v));
+    }
+
+    /// The pairs whose key lies in `range`.
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> &[(K, V)] {
+        &self.entries[range_index(self.entries.len(), range, |i, k| self.entries[i].0.cmp(k))]
+    }
+
+    /// Each distinct key with all of its pairs, in key order.
+    pub fn groups(&self) -> impl Iterator<Item = (&K, &[(K, V)])> {
+        self.entries.chunk_by(|a, b| a.0 == b.0).map(|g| (&g[0].0, g))
+    }
+
+    pub fn into_vec(self) -> Vec<(K, V)> {
+        self.entries
+    }
+}
+
+/// Collects pairs, keeping the order of equal keys.
+impl<K: Ord, V> FromIterator<(K, V)> for FlatMultiMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut entries = iter.into_iter().collect::<Vec<_>>();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        FlatMultiMap { entries }
+    }
+}
+
+impl<K: Ord, V> Extend<(K, V)> for FlatMultiMap<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        self.entries.extend(iter);
+        self.entries.sort_by(|a, b| a.0.cmp(&b.0));
+    }
+}
+
+impl<K, V> IntoIterator for FlatMultiMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = std::vec::IntoIter<(K, V)>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<K: Ord, V> LowerBound for FlatMultiMap<K, V> {
+    type Item = K;
+    fn lower_bound(&self, x: &Self::Item) -> Result<usize, usize> {
+        self.lower_bound_by(|y| y.cmp(x))
+    }
+
+    fn lower_bound_by<'a, F>(&'a self, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> Ordering,
+    {
+        lower_bound_index(self.len(), |i| f(&self.entries[i].0))
+    }
+
+    fn lower_bound_by_key<'a, Q, F>(&'a self, k: &Q, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> Q,
+        Q: Ord,
+    {
+        self.lower_bound_by(|y| f(y).cmp(k))
+    }
+}
+
+impl<K: Ord, V> UpperBound for FlatMultiMap<K, V> {
+    type Item = K;
+    fn upper_bound(&self, x: &Self::Item) -> Result<usize, usize> {
+        self.upper_bound_by(|y| y.cmp(x))
+    }
+
+    fn upper_bound_by<'a, F>(&'a self, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> Ordering,
+    {
+        upper_bound_index(self.len(), |i| f(&self.entries[i].0))
+    }
+
+    fn upper_bound_by_key<'a, Q, F>(&'a self, k: &Q, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> Q,
+        Q: Ord,
+    {
+        self.upper_bound_by(|y| f(y).cmp(k))
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl<K: rkyv::Archive, V: rkyv::Archive> ArchivedFlatMultiMap<K, V>
+where
+    K::Archived: Ord,
+{
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// All archived pairs, sorted by key.
+    pub fn as_slice(&self) -> &[rkyv::tuple::ArchivedTuple2<K::Archived, V::Archived>] {
+        &self.entries
+    }
+
+    /// Index range of the pairs with key `key`.
+    pub fn equal_range(&self, key: &K::Archived) -> Range<usize> {
+        let lo = self.lower_bound(key).unwrap_or_else(|e| e);
+        let hi = self.upper_bound(key).unwrap_or_else(|e| e);
+        lo..hi
+    }
+
+    /// The pairs with key `key`, in insertion order.
+    pub fn get_all(&self, key: &K::Archived) -> &[rkyv::tuple::ArchivedTuple2<K::Archived, V::Archived>] {
+        &self.entries[self.equal_range(key)]
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl<K: rkyv::Archive, V: rkyv::Archive> LowerBound for ArchivedFlatMultiMap<K, V>
+where
+    K::Archived: Ord,
+{
+    type Item = K::Archived;
+    fn lower_bound(&self, x: &Self::Item) -> Result<usize, usize> {
+        self.lower_bound_by(|y| y.cmp(x))
+    }
+
+    fn lower_bound_by<'a, F>(&'a self, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> Ordering,
+    {
+        lower_bound_index(self.len(), |i| f(&self.entries[i].0))
+    }
+
+    fn lower_bound_by_key<'a, Q, F>(&'a self, k: &Q, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> Q,
+        Q: Ord,
+    {
+        self.lower_bound_by(|y| f(y).cmp(k))
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl<K: rkyv::Archive, V: rkyv::Archive> UpperBound for ArchivedFlatMultiMap<K, V>
+where
+    K::Archived: Ord,
+{
+    type Item = K::Archived;
+    fn upper_bound(&self, x: &Self::Item) -> Result<usize, usize> {
+        self.upper_bound_by(|y| y.cmp(x))
+    }
+
+    fn upper_bound_by<'a, F>(&'a self, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> Ordering,
+    {
+        upper_bound_index(self.len(), |i| f(&self.entries[i].0))
+    }
+
+    fn upper_bound_by_key<'a, Q, F>(&'a self, k: &Q, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> Q,
+        Q: Ord,
+    {
+        self.upper_bound_by(|y| f(y).cmp(k))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::{FlatMultiMap, LowerBound, UpperBound};
+
+    #[test]
+    fn test_flat_multimap() {
+        let mut m = [(3, "c1"), (1, "a1"), (3, "c2"), (2, "b1"), (1, "a2")]
+            .into_iter()
+            .collect::<FlatMultiMap<_, _>>();
+        assert_eq!(m.get_all(&1), &[(1, "a1"), (1, "a2")]);
+        assert_eq!(m.count(&3), 2);
+        assert_eq!(m.lower_bound(&2), Ok(2));
+        assert_eq!(m.upper_bound(&3), Err(5));
+        assert_eq!(m.range(2..).len(), 3);
+
+        assert_eq!(m.insert(2, "b2"), 3);
+        assert_eq!(m.get_all(&2), &[(2, "b1"), (2, "b2")]);
+        m.values_mut(&2).for_each(|v| *v = "b");
+        assert_eq!(m.get_all(&2), &[(2, "b"), (2, "b")]);
+
+        assert_eq!(m.remove_all(&1).len(), 2);
+        assert!(m.remove_all(&1).is_empty());
+        assert!(!m.contains_key(&1));
+        m.extend([(0, "z")]);
+        let keys = m.groups().map(|(k, _)| *k).collect::<Vec<_>>();
+        assert_eq!(keys, vec![0, 2, 3]);
+        m.retain(|k, _| *k != 2);
+        assert_eq!(m.into_vec(), vec![(0, "z"), (3, "c1"), (3, "c2")]);
+    }
+
+    #[cfg(feature = "rkyv")]
+    #[test]
+    fn test_archived_flat_multimap() {
+        use bound_stl::ArchivedFlatMultiMap;
+        use rkyv::rancor::Error;
+
+        let m = [(3u32, 30u16), (1, 10), (3, 31), (2, 20), (1, 11)]
+            .into_iter()
+            .collect::<FlatMultiMap<_, _>>();
+        let bytes = rkyv::to_bytes::<Error>(&m).unwrap();
+        let archived = rkyv::access::<ArchivedFlatMultiMap<u32, u16>, Error>(&bytes).unwrap();
+        assert_eq!(archived.len(), m.len());
+        for k in 0..5 {
+            assert_eq!(archived.lower_bound(&k.into()), m.lower_bound(&k));
+            assert_eq!(archived.upper_bound_by_key(&k, |x| x.to_native()), m.upper_bound(&k));
+            assert_eq!(archived.equal_range(&k.into()), m.equal_range(&k));
+        }
+        let values = archived.get_all(&3.into()).iter().map(|p| p.1.to_native());
+        assert_eq!(values.collect::<Vec<_>>(), vec![30, 31]);
+        let back = rkyv::deserialize::<FlatMultiMap<u32, u16>, Error>(archived).unwrap();
+        assert_eq!(back, m);
+    }
+}
@@ -0,0 +1,104 @@
+use crate::{LowerBound, UpperBound};
+use std::cell::{Ref, RefMut};
+use std::cmp::Ordering;
+use std::sync::{MutexGuard, RwLockReadGuard, RwLockWriteGuard};
+
+/// Bounds on borrow and lock guards of a sorted `Vec`, so `RefCell`, `Mutex` and `RwLock`
+/// contents are searched through the guard directly.
+/// ```
+/// # use bound_stl::{LowerBound, UpperBound};
+/// # use std::sync::{Mutex, RwLock};
+/// let ids = Mutex::new(vec![1, 3, 3, 7]);
+/// assert_eq!(ids.lock().unwrap().lower_bound(&3), Ok(1));
+///
+/// let ids = RwLock::new(vec![1, 3, 3, 7]);
+/// let guard = ids.read().unwrap();
+/// assert_eq!(guard.upper_bound(&3), Ok(3));
+/// assert_eq!(guard.lower_bound(&8), Err(4));
+/// ```
+macro_rules! impl_guard_bounds {
+    ($($guard:ident),*) => {$(
+        impl<T: Ord> LowerBound for $guard<'_, Vec<T>> {
+            type Item = T;
+            fn lower_bound(&self, x: &Self::Item) -> Result<usize, usize> {
+                self.as_slice().lower_bound(x)
+            }
+
+            fn lower_bound_by<'a, F>(&'a self, f: F) -> Result<usize, usize>
+            where
+                F: FnMut(&'a Self::Item) -> Ordering,
+            {
+                self.as_slice().lower_bound_by(f)
+            }
+
+            fn lower_bound_by_key<'a, K, F>(&'a self, k: &K, f: F) -> Result<usize, usize>
+            where
+                F: FnMut(&'a Self::Item) -> K,
+                K: Ord,
+            {
+                self.as_slice().lower_bound_by_key(k, f)
+            }
+        }
+
+        impl<T: Ord> UpperBound for $guard<'_, Vec<T>> {
+            type Item = T;
+            fn upper_bound(&self, x: &Self::Item) -> Result<usize, usize> {
+                self.as_slice().upper_bound(x)
+            }
+
+            fn upper_bound_by<'a, F>(&'a self, f: F) -> Result<usize, usize>
+            where
+                F: FnMut(&'a Self::Item) -> Ordering,
+            {
+                self.as_slice().upper_bound_by(f)
+            }
+
+            fn upper_bound_by_key<'a, K, F>(&'a self, k: &K, f: F) -> Result<usize, usize>
+            where
+                F: FnMut(&'a Self::Item) -> K,
+                K: Ord,
+            {
+                self.as_slice().upper_bound_by_key(k, f)
+            }
+        }
+    )*};
+}
+
+impl_guard_bounds!(Ref, RefMut, MutexGuard, RwLockReadGuard, RwLockWriteGuard);
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::{LowerBound, UpperBound};
+    use std::cell::RefCell;
+    use std::sync::{Mutex, RwLock};
+
+    /// Generic code taking any bound-searchable type accepts the guards as they are.
+    fn span<S: LowerBound<Item = u8> + UpperBound<Item = u8>>(s: &S, x: u8) -> (usize, usize) {
+        (
+            s.lower_bound(&x).unwrap_or_else(|e| e),
+            s.upper_bound(&x).unwrap_or_else(|e| e),
+        )
+    }
+
+    #[test]
+    fn test_guard_bounds() {
+        let v = vec![2u8, 4, 4, 4, 9];
+        let cell = RefCell::new(v.clone());
+        let mutex = Mutex::new(v.clone());
+        let rw = RwLock::new(v.clone());
+        for x in 0..11 {
+            let expect = span(&v, x);
+            assert_eq!(span(&cell.borrow(), x), expect);
+            assert_eq!(span(&cell.borrow_mut(), x), expect);
+            assert_eq!(span(&mutex.lock().unwrap(), x), expect);
+            assert_eq!(span(&rw.read().unwrap(), x), expect);
+            assert_eq!(span(&rw.write().unwrap(), x), expect);
+        }
+
+        let mut guard = mutex.lock().unwrap();
+        let pos = guard.upper_bound(&4).unwrap_or_else(|e| e);
+        guard.insert(pos, 5);
+        assert_eq!(guard.lower_bound_by_key(&10, |&x| x * 2), Ok(4));
+        assert_eq!(cell.borrow().upper_bound_by(|x| x.cmp(&9)), Err(5));
+    }
+}
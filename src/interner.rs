@@ -0,0 +1,161 @@
+use crate::{lower_bound_index, upper_bound_index};
+use std::cmp::Ordering;
+use std::ops::Range;
+
+/// An id handed out by a [`SortedInterner`].
+///
+/// Ids are the ranks of the strings, so comparing two symbols of the same interner
+/// compares their strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// A deduplicated, sorted set of strings packed into one arena, with order-preserving ids.
+///
+/// The interner is built in one go from any iterator of strings, so every id is the rank of its
+/// string and stays valid for the interner's lifetime. String queries follow the `Result`
+/// convention of `LowerBound`, where an index is also the id of the string found there.
+/// ```
+/// # use bound_stl::SortedInterner;
+/// let names = ["pear", "apple", "plum", "apple", "peach"];
+/// let interner = names.iter().collect::<SortedInterner>();
+/// assert_eq!(interner.len(), 4);
+/// let pear = interner.get("pear").unwrap();
+/// let apple = interner.get("apple").unwrap();
+/// assert!(apple < pear);
+/// assert_eq!(interner.resolve(pear), "pear");
+/// assert_eq!(interner.lower_bound("pf"), Ok(3));
+/// let pe = interner.prefix("pe").map(|(_, s)| s).collect::<Vec<_>>();
+/// assert_eq!(pe, ["peach", "pear"]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SortedInterner {
+    arena: String,
+    /// `ends[i]`: end offset of string `i` in the arena, which starts where string `i - 1` ends
+    ends: Vec<usize>,
+}
+
+impl SortedInterner {
+    pub fn new<I, S>(strings: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut owned = strings.into_iter().collect::<Vec<_>>();
+        owned.sort_unstable_by(|a, b| a.as_ref().cmp(b.as_ref()));
+        owned.dedup_by(|a, b| a.as_ref() == b.as_ref());
+        assert!(owned.len() <= u32::MAX as usize, "too many strings for 32-bit symbols");
+        let mut arena = String::with_capacity(owned.iter().map(|s| s.as_ref().len()).sum());
+        let mut ends = Vec::with_capacity(owned.len());
+        for s in &owned {
+            arena.push_str(s.as_ref());
+            ends.push(arena.len());
+        }
+        SortedInterner { arena, ends }
+    }
+
+    pub fn len(&self) -> usize {
+        self.ends.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ends.is_empty()
+    }
+
+    /// Panics if `sym` comes from a larger interner.
+    pub fn resolve(&self, sym: Symbol) -> &str {
+        let i = sym.index();
+        let start = if i == 0 { 0 } else { self.ends[i - 1] };
+        &self.arena[start..self.ends[i]]
+    }
+
+    pub fn get(&self, s: &str) -> Option<Symbol> {
+        let i = self.lower_bound(s).ok()?;
+        (self.resolve(Symbol(i as u32)) == s).then_some(Symbol(i as u32))
+    }
+
+    pub fn contains(&self, s: &str) -> bool {
+        self.get(s).is_some()
+    }
+
+    /// Index of the first string not less than `s`.
+    pub fn lower_bound(&self, s: &str) -> Result<usize, usize> {
+        lower_bound_index(self.len(), |i| self.resolve(Symbol(i as u32)).cmp(s))
+    }
+
+    /// Index of the first string greater than `s`.
+    pub fn upper_bound(&self, s: &str) -> Result<usize, usize> {
+        upper_bound_index(self.len(), |i| self.resolve(Symbol(i as u32)).cmp(s))
+    }
+
+    /// Index range of the strings starting with `prefix`.
+    pub fn prefix_range(&self, prefix: &str) -> Range<usize> {
+        let lo = self.lower_bound(prefix).unwrap_or_else(|e| e);
+        // the strings with the prefix form a run starting at `lo`
+        let len = self.ends.len() - lo;
+        let run = lower_bound_index(len, |i| {
+            match self.resolve(Symbol((lo + i) as u32)).starts_with(prefix) {
+                true => Ordering::Less,
+                false => Ordering::Greater,
+            }
+        });
+        lo..lo + run.unwrap_or_else(|e| e)
+    }
+
+    /// The strings starting with `prefix`, in order.
+    pub fn prefix(&self, prefix: &str) -> impl Iterator<Item = (Symbol, &str)> {
+        self.prefix_range(prefix)
+            .map(|i| Symbol(i as u32))
+            .map(|sym| (sym, self.resolve(sym)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Symbol, &str)> {
+        (0..self.len() as u32).map(Symbol).map(|sym| (sym, self.resolve(sym)))
+    }
+
+    /// Total bytes of string data, after deduplication.
+    pub fn arena_len(&self) -> usize {
+        self.arena.len()
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<S> for SortedInterner {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        SortedInterner::new(iter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::{LowerBound, SortedInterner, UpperBound};
+
+    #[test]
+    fn test_sorted_interner() {
+        let words = ["b", "ab", "abc", "", "ab", "abd", "b", "ba", "c"];
+        let interner = SortedInterner::new(words);
+        let mut sorted = words.to_vec();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(interner.len(), sorted.len());
+        assert_eq!(interner.arena_len(), sorted.concat().len());
+        assert!(interner.iter().map(|(_, s)| s).eq(sorted.iter().copied()));
+        for (i, &w) in sorted.iter().enumerate() {
+            let sym = interner.get(w).unwrap();
+            assert_eq!(sym.index(), i);
+            assert_eq!(interner.resolve(sym), w);
+        }
+        for q in ["", "a", "ab", "abb", "abz", "b", "bb", "d"] {
+            assert_eq!(interner.lower_bound(q), sorted.lower_bound(&q), "q = {q:?}");
+            assert_eq!(interner.upper_bound(q), sorted.upper_bound(&q), "q = {q:?}");
+            let expect = sorted.iter().filter(|w| w.starts_with(q)).copied().collect::<Vec<_>>();
+            assert!(interner.prefix(q).map(|(_, s)| s).eq(expect), "q = {q:?}");
+        }
+        assert_eq!(interner.get("abb"), None);
+        assert_eq!(SortedInterner::default().prefix_range("x"), 0..0);
+    }
+}
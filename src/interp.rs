@@ -0,0 +1,166 @@
+use std::fmt;
+
+/// What [`Interp1d`] does with an `x` outside the table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Extrapolate {
+    /// Hold the first or last `y`, as NumPy's `interp` does.
+    #[default]
+    Clamp,
+    /// Return [`OutOfRange`].
+    Error,
+    /// Extend the first or last segment.
+    Linear,
+}
+
+/// The `x` that [`Interp1d::eval`] refused under [`Extrapolate::Error`]; NaN is always refused.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OutOfRange(pub f64);
+
+impl fmt::Display for OutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is outside the interpolation table", self.0)
+    }
+}
+
+impl std::error::Error for OutOfRange {}
+
+/// Piecewise-linear interpolation of the points `(xs[i], ys[i])` at `x`, clamping outside them.
+///
+/// `xs` must be strictly increasing and as long as `ys`; panics if they are empty.
+/// The bracketing segment is found with a lower bound, so a lookup costs `O(log n)`.
+/// ```
+/// # use bound_stl::interp;
+/// let xs = [0.0, 1.0, 3.0];
+/// let ys = [10.0, 20.0, 0.0];
+/// assert_eq!(interp(0.5, &xs, &ys), 15.0);
+/// assert_eq!(interp(2.0, &xs, &ys), 10.0);
+/// assert_eq!(interp(-5.0, &xs, &ys), 10.0);
+/// assert_eq!(interp(9.0, &xs, &ys), 0.0);
+/// ```
+pub fn interp(x: f64, xs: &[f64], ys: &[f64]) -> f64 {
+    assert_eq!(xs.len(), ys.len(), "xs and ys differ in length");
+    assert!(!xs.is_empty(), "empty interpolation table");
+    eval(xs, ys, Extrapolate::Clamp, x).unwrap_or(f64::NAN)
+}
+
+/// A prebuilt table for [`interp`]-style lookups, with a choice of [`Extrapolate`] policy.
+/// ```
+/// # use bound_stl::{Extrapolate, Interp1d, OutOfRange};
+/// let curve = Interp1d::new(vec![0.0, 10.0, 20.0], vec![0.0, 1.0, 4.0]);
+/// assert_eq!(curve.eval(15.0), Ok(2.5));
+/// assert_eq!(curve.eval(30.0), Ok(4.0));
+///
+/// let strict = curve.clone().with_extrapolation(Extrapolate::Error);
+/// assert_eq!(strict.eval(30.0), Err(OutOfRange(30.0)));
+///
+/// let linear = curve.with_extrapolation(Extrapolate::Linear);
+/// assert_eq!(linear.eval(30.0), Ok(7.0));
+/// assert_eq!(linear.eval(-10.0), Ok(-1.0));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Interp1d {
+    xs: Vec<f64>,
+    ys: Vec<f64>,
+    extrapolate: Extrapolate,
+}
+
+impl Interp1d {
+    /// Panics if the tables are empty, differ in length, or `xs` is not strictly increasing.
+    pub fn new(xs: Vec<f64>, ys: Vec<f64>) -> Self {
+        assert_eq!(xs.len(), ys.len(), "xs and ys differ in length");
+        assert!(!xs.is_empty(), "empty interpolation table");
+        assert!(xs.windows(2).all(|w| w[0] < w[1]), "xs is not strictly increasing");
+        Interp1d {
+            xs,
+            ys,
+            extrapolate: Extrapolate::default(),
+        }
+    }
+
+    pub fn with_extrapolation(mut self, extrapolate: Extrapolate) -> Self {
+        self.extrapolate = extrapolate;
+        self
+    }
+
+    pub fn eval(&self, x: f64) -> Result<f64, OutOfRange> {
+        eval(&self.xs, &self.ys, self.extrapolate, x)
+    }
+
+    /// The domain covered without extrapolating.
+    pub fn domain(&self) -> (f64, f64) {
+        (self.xs[0], self.xs[self.xs.len() - 1])
+    }
+
+    pub fn xs(&self) -> &[f64] {
+        &self.xs
+    }
+
+    pub fn ys(&self) -> &[f64] {
+        &self.ys
+    }
+}
+
+fn eval(xs: &[f64], ys: &[f64], extrapolate: Extrapolate, x: f64) -> Result<f64, OutOfRange> {
+    if x.is_nan() {
+        return Err(OutOfRange(x));
+    }
+    let n = xs.len();
+    // first x not less than `x`; the segment [i - 1, i] brackets it when 0 < i < n
+    let i = xs.partition_point(|&v| v < x);
+    if i < n && xs[i] == x {
+        return Ok(ys[i]);
+    }
+    let segment = if 0 < i && i < n {
+        i
+    } else {
+        match extrapolate {
+            Extrapolate::Clamp => return Ok(if i == 0 { ys[0] } else { ys[n - 1] }),
+            Extrapolate::Error => return Err(OutOfRange(x)),
+            Extrapolate::Linear if n == 1 => return Ok(ys[0]),
+            Extrapolate::Linear => i.clamp(1, n - 1),
+        }
+    };
+    let (x0, x1, y0, y1) = (xs[segment - 1], xs[segment], ys[segment - 1], ys[segment]);
+    Ok(y0 + (y1 - y0) * (x - x0) / (x1 - x0))
+}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::{interp, Extrapolate, Interp1d, OutOfRange};
+
+    #[test]
+    fn test_interp() {
+        let xs = [-2.0, 0.0, 0.5, 4.0];
+        let ys = [4.0, 0.0, 1.0, -6.0];
+        for (x, y) in [
+            (-3.0, 4.0),
+            (-2.0, 4.0),
+            (-1.0, 2.0),
+            (0.25, 0.5),
+            (2.25, -2.5),
+            (4.0, -6.0),
+            (5.0, -6.0),
+        ] {
+            assert_eq!(interp(x, &xs, &ys), y, "x = {x}");
+        }
+        assert!(interp(f64::NAN, &xs, &ys).is_nan());
+
+        let table = Interp1d::new(xs.to_vec(), ys.to_vec());
+        assert_eq!(table.domain(), (-2.0, 4.0));
+        let linear = table.clone().with_extrapolation(Extrapolate::Linear);
+        assert_eq!(linear.eval(-3.0), Ok(6.0));
+        assert_eq!(linear.eval(6.0), Ok(-10.0));
+        let strict = table.with_extrapolation(Extrapolate::Error);
+        assert_eq!(strict.eval(-2.0), Ok(4.0));
+        assert_eq!(strict.eval(4.0 + 1e-9), Err(OutOfRange(4.0 + 1e-9)));
+
+        let single = Interp1d::new(vec![1.0], vec![7.0]).with_extrapolation(Extrapolate::Linear);
+        assert_eq!(single.eval(-100.0), Ok(7.0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_interp1d_unsorted() {
+        Interp1d::new(vec![0.0, 0.0], vec![1.0, 2.0]);
+    }
+}
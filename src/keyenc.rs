@@ -0,0 +1,398 @@
+//! Order-preserving key encoding.
+//!
+//! Typed values are encoded into byte strings whose `memcmp` order is the logical order of
+//! the values, so multi-field keys can be searched by the byte-oriented types of this crate,
+//! such as [`StridedSorted`](crate::StridedSorted).
+//!
+//! - unsigned integers are written big-endian, signed integers with the sign bit flipped;
+//! - floats follow [`f64::total_cmp`]: `-NaN < -inf < ... < -0.0 < 0.0 < ... < inf < NaN`;
+//! - strings and byte strings escape `0x00` as `00 ff` and end with `00 01`,
+//!   so a string sorts before any longer string it is a prefix of;
+//! - `None` sorts before every `Some`, tuples compare field by field;
+//! - [`Descending`] inverts the order of the wrapped field.
+//!
+//! ```
+//! use bound_stl::keyenc::{self, Descending};
+//!
+//! let a = keyenc::encode(&("apple", -3i32, Descending(2.5f64)));
+//! let b = keyenc::encode(&("apple", -3i32, Descending(1.0f64)));
+//! let c = keyenc::encode(&("apples", i32::MIN, Descending(9.0f64)));
+//! assert!(a < b && b < c);
+//! let decoded = keyenc::decode::<(String, i32, Descending<f64>)>(&b).unwrap();
+//! assert_eq!(decoded, ("apple".to_string(), -3, Descending(1.0)));
+//! ```
+
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The input ended in the middle of a value.
+    UnexpectedEnd,
+    /// The bytes are not a valid encoding of the requested type.
+    Invalid(&'static str),
+    /// [`decode`] consumed a whole value but input was left over.
+    TrailingBytes,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::UnexpectedEnd => write!(f, "unexpected end of key"),
+            DecodeError::Invalid(what) => write!(f, "invalid key encoding: {what}"),
+            DecodeError::TrailingBytes => write!(f, "trailing bytes after key"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// A value that can be written as an order-preserving key.
+pub trait KeyEncode {
+    fn encode_key(&self, out: &mut Vec<u8>);
+}
+
+/// A value that can be read back from its [`KeyEncode`] form.
+pub trait KeyDecode: Sized {
+    fn decode_key(input: &mut KeyReader<'_>) -> Result<Self, DecodeError>;
+}
+
+/// Encodes `value` into a fresh buffer.
+pub fn encode<T: KeyEncode + ?Sized>(value: &T) -> Vec<u8> {
+    let mut out = Vec::new();
+    value.encode_key(&mut out);
+    out
+}
+
+/// Decodes a whole key; the input must hold exactly one value.
+pub fn decode<T: KeyDecode>(bytes: &[u8]) -> Result<T, DecodeError> {
+    let mut input = KeyReader::new(bytes);
+    let value = T::decode_key(&mut input)?;
+    if input.is_empty() {
+        Ok(value)
+    } else {
+        Err(DecodeError::TrailingBytes)
+    }
+}
+
+/// Cursor over an encoded key, used by [`KeyDecode`] implementations.
+#[derive(Debug, Clone)]
+pub struct KeyReader<'a> {
+    input: &'a [u8],
+    invert: bool,
+}
+
+impl<'a> KeyReader<'a> {
+    pub fn new(input: &'a [u8]) -> Self {
+        KeyReader { input, invert: false }
+    }
+
+    /// The bytes not consumed yet.
+    pub fn remaining(&self) -> &'a [u8] {
+        self.input
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.input.is_empty()
+    }
+
+    pub fn read_byte(&mut self) -> Result<u8, DecodeError> {
+        let (&b, rest) = self.input.split_first().ok_or(DecodeError::UnexpectedEnd)?;
+        self.input = rest;
+        Ok(if self.invert { !b } else { b })
+    }
+
+    pub fn read_array<const N: usize>(&mut self) -> Result<[u8; N], DecodeError> {
+        let mut buf = [0; N];
+        for b in buf.iter_mut() {
+            *b = self.read_byte()?;
+        }
+        Ok(buf)
+    }
+}
+
+macro_rules! impl_unsigned {
+    ($($t:ty),*) => {$(
+        impl KeyEncode for $t {
+            fn encode_key(&self, out: &mut Vec<u8>) {
+                out.extend_from_slice(&self.to_be_bytes());
+            }
+        }
+
+        impl KeyDecode for $t {
+            fn decode_key(input: &mut KeyReader<'_>) -> Result<Self, DecodeError> {
+                Ok(<$t>::from_be_bytes(input.read_array()?))
+            }
+        }
+    )*};
+}
+
+impl_unsigned!(u8, u16, u32, u64, u128);
+
+macro_rules! impl_signed {
+    ($($t:ty => $u:ty),*) => {$(
+        impl KeyEncode for $t {
+            fn encode_key(&self, out: &mut Vec<u8>) {
+                let flipped = (*self as $u) ^ (1 << (<$u>::BITS - 1));
+                out.extend_from_slice(&flipped.to_be_bytes());
+            }
+        }
+
+        impl KeyDecode for $t {
+            fn decode_key(input: &mut KeyReader<'_>) -> Result<Self, DecodeError> {
+                let flipped = <$u>::from_be_bytes(input.read_array()?);
+                Ok((flipped ^ (1 << (<$u>::BITS - 1))) as $t)
+            }
+        }
+    )*};
+}
+
+impl_signed!(i8 => u8, i16 => u16, i32 => u32, i64 => u64, i128 => u128);
+
+macro_rules! impl_float {
+    ($($t:ty => $u:ty),*) => {$(
+        impl KeyEncode for $t {
+            fn encode_key(&self, out: &mut Vec<u8>) {
+                let bits = self.to_bits();
+                let sign = 1 << (<$u>::BITS - 1);
+                let ordered = if bits & sign != 0 { !bits } else { bits ^ sign };
+                out.extend_from_slice(&ordered.to_be_bytes());
+            }
+        }
+
+        impl KeyDecode for $t {
+            fn decode_key(input: &mut KeyReader<'_>) -> Result<Self, DecodeError> {
+                let ordered = <$u>::from_be_bytes(input.read_array()?);
+                let sign = 1 << (<$u>::BITS - 1);
+                let bits = if ordered & sign != 0 { ordered ^ sign } else { !ordered };
+                Ok(<$t>::from_bits(bits))
+            }
+        }
+    )*};
+}
+
+impl_float!(f32 => u32, f64 => u64);
+
+impl KeyEncode for bool {
+    fn encode_key(&self, out: &mut Vec<u8>) {
+        out.push(*self as u8);
+    }
+}
+
+impl KeyDecode for bool {
+    fn decode_key(input: &mut KeyReader<'_>) -> Result<Self, DecodeError> {
+        match input.read_byte()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(DecodeError::Invalid("bool")),
+        }
+    }
+}
+
+impl KeyEncode for char {
+    fn encode_key(&self, out: &mut Vec<u8>) {
+        (*self as u32).encode_key(out);
+    }
+}
+
+impl KeyDecode for char {
+    fn decode_key(input: &mut KeyReader<'_>) -> Result<Self, DecodeError> {
+        char::from_u32(u32::decode_key(input)?).ok_or(DecodeError::Invalid("char"))
+    }
+}
+
+const ESCAPE: u8 = 0x00;
+const ESCAPED_ZERO: u8 = 0xff;
+const TERMINATOR: u8 = 0x01;
+
+impl KeyEncode for [u8] {
+    fn encode_key(&self, out: &mut Vec<u8>) {
+        for &b in self {
+            out.push(b);
+            if b == ESCAPE {
+                out.push(ESCAPED_ZERO);
+            }
+        }
+        out.extend_from_slice(&[ESCAPE, TERMINATOR]);
+    }
+}
+
+impl KeyEncode for Vec<u8> {
+    fn encode_key(&self, out: &mut Vec<u8>) {
+        self.as_slice().encode_key(out);
+    }
+}
+
+impl KeyDecode for Vec<u8> {
+    fn decode_key(input: &mut KeyReader<'_>) -> Result<Self, DecodeError> {
+        let mut bytes = Vec::new();
+        loop {
+            match input.read_byte()? {
+                ESCAPE => match input.read_byte()? {
+                    ESCAPED_ZERO => bytes.push(0),
+                    TERMINATOR => return Ok(bytes),
+                    _ => return Err(DecodeError::Invalid("byte string escape")),
+                },
+                b => bytes.push(b),
+            }
+        }
+    }
+}
+
+impl KeyEncode for str {
+    fn encode_key(&self, out: &mut Vec<u8>) {
+        self.as_bytes().encode_key(out);
+    }
+}
+
+impl KeyEncode for String {
+    fn encode_key(&self, out: &mut Vec<u8>) {
+        self.as_bytes().encode_key(out);
+    }
+}
+
+impl KeyDecode for String {
+    fn decode_key(input: &mut KeyReader<'_>) -> Result<Self, DecodeError> {
+        String::from_utf8(Vec::<u8>::decode_key(input)?).map_err(|_| DecodeError::Invalid("utf-8"))
+    }
+}
+
+impl<T: KeyEncode + ?Sized> KeyEncode for &T {
+    fn encode_key(&self, out: &mut Vec<u8>) {
+        (**self).encode_key(out);
+    }
+}
+
+impl<T: KeyEncode> KeyEncode for Option<T> {
+    fn encode_key(&self, out: &mut Vec<u8>) {
+        match self {
+            None => out.push(0),
+            Some(v) => {
+                out.push(1);
+                v.encode_key(out);
+            }
+        }
+    }
+}
+
+impl<T: KeyDecode> KeyDecode for Option<T> {
+    fn decode_key(input: &mut KeyReader<'_>) -> Result<Self, DecodeError> {
+        match input.read_byte()? {
+            0 => Ok(None),
+            1 => Ok(Some(T::decode_key(input)?)),
+            _ => Err(DecodeError::Invalid("option tag")),
+        }
+    }
+}
+
+/// Wraps a field so that it sorts in descending order.
+///
+/// Every encoding above is prefix-free, so inverting the bytes of a field reverses its order
+/// without disturbing the fields that follow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Descending<T>(pub T);
+
+impl<T: KeyEncode> KeyEncode for Descending<T> {
+    fn encode_key(&self, out: &mut Vec<u8>) {
+        let start = out.len();
+        self.0.encode_key(out);
+        out[start..].iter_mut().for_each(|b| *b = !*b);
+    }
+}
+
+impl<T: KeyDecode> KeyDecode for Descending<T> {
+    fn decode_key(input: &mut KeyReader<'_>) -> Result<Self, DecodeError> {
+        input.invert = !input.invert;
+        let value = T::decode_key(input);
+        input.invert = !input.invert;
+        value.map(Descending)
+    }
+}
+
+macro_rules! impl_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: KeyEncode),+> KeyEncode for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn encode_key(&self, out: &mut Vec<u8>) {
+                let ($($name,)+) = self;
+                $($name.encode_key(out);)+
+            }
+        }
+
+        impl<$($name: KeyDecode),+> KeyDecode for ($($name,)+) {
+            fn decode_key(input: &mut KeyReader<'_>) -> Result<Self, DecodeError> {
+                Ok(($($name::decode_key(input)?,)+))
+            }
+        }
+    };
+}
+
+impl_tuple!(A);
+impl_tuple!(A, B);
+impl_tuple!(A, B, C);
+impl_tuple!(A, B, C, D);
+impl_tuple!(A, B, C, D, E);
+impl_tuple!(A, B, C, D, E, F);
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::keyenc::{self, DecodeError, Descending, KeyDecode, KeyEncode};
+    use std::fmt::Debug;
+
+    /// `values` must be listed in ascending order.
+    fn check_order<T: KeyEncode + KeyDecode + PartialEq + Debug>(values: &[T]) {
+        let keys = values.iter().map(keyenc::encode).collect::<Vec<_>>();
+        for (w, v) in keys.windows(2).zip(values.windows(2)) {
+            assert!(w[0] < w[1], "{:?} should sort before {:?}", v[0], v[1]);
+        }
+        for (k, v) in keys.iter().zip(values) {
+            assert_eq!(&keyenc::decode::<T>(k).unwrap(), v);
+        }
+    }
+
+    #[test]
+    fn test_scalars() {
+        check_order(&[0u16, 1, 255, 256, u16::MAX]);
+        check_order(&[i64::MIN, -256, -1, 0, 1, 255, i64::MAX]);
+        check_order(&[i8::MIN, -1, 0, i8::MAX]);
+        check_order(&[f64::NEG_INFINITY, -1e300, -1.5, -0.0, 0.0, 1e-300, 2.5, f64::INFINITY]);
+        check_order(&[-3.0f32, -0.5, 0.0, 7.25]);
+        check_order(&[false, true]);
+        check_order(&['\0', 'A', 'a', 'é', '😀']);
+        assert!(keyenc::encode(&f64::INFINITY) < keyenc::encode(&f64::NAN));
+    }
+
+    #[test]
+    fn test_strings_and_composites() {
+        check_order(&["", "\0", "\0\0", "a", "a\0", "a\0b", "ab", "b"].map(String::from));
+        check_order(&[vec![], vec![0u8], vec![0, 255], vec![1], vec![255, 0]]);
+        check_order(&[None, Some(-5i32), Some(0), Some(9)]);
+        check_order(&[
+            ("a".to_string(), Descending(9u8), 1i16),
+            ("a".to_string(), Descending(2u8), -4i16),
+            ("a".to_string(), Descending(2u8), 3i16),
+            ("ab".to_string(), Descending(200u8), 0i16),
+        ]);
+        check_order(&[
+            Descending("b".to_string()),
+            Descending("ab".to_string()),
+            Descending("a".to_string()),
+            Descending(String::new()),
+        ]);
+        check_order(&[(Descending(Some(3u32)), 0u8), (Descending(None), 0u8)]);
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(keyenc::decode::<u32>(&[0, 0, 1]), Err(DecodeError::UnexpectedEnd));
+        assert_eq!(keyenc::decode::<u8>(&[0, 0]), Err(DecodeError::TrailingBytes));
+        assert_eq!(
+            keyenc::decode::<String>(b"ab\0\x07"),
+            Err(DecodeError::Invalid("byte string escape"))
+        );
+        assert_eq!(
+            keyenc::decode::<String>(&[0xff, 0, 1]),
+            Err(DecodeError::Invalid("utf-8"))
+        );
+        assert_eq!(keyenc::decode::<bool>(&[2]), Err(DecodeError::Invalid("bool")));
+    }
+}
@@ -0,0 +1,96 @@
+use crate::{LowerBound, UpperBound};
+
+/// Bounds over k-sorted data: every element is at most `k` positions away from its place in
+/// the sorted order, as in a nearly-sorted event buffer.
+///
+/// The results are those of the sorted order, i.e. `lower_bound_lenient` counts the elements
+/// less than `x`, found with a plain binary search plus a scan of the `±2k` neighbourhood,
+/// so a query costs `O(log n + k)`. Like `lower_bound`, it returns `Err` when all elements are
+/// less than `x`. Use [`LenientBound::disorder`] to measure `k`.
+/// ```
+/// # use bound_stl::LenientBound;
+/// let v = vec![2, 1, 3, 5, 4, 6, 8, 7];
+/// assert_eq!(v.disorder(), 1);
+/// assert_eq!(v.lower_bound_lenient(&4, 1), Ok(3));
+/// assert_eq!(v.upper_bound_lenient(&1, 1), Ok(1));
+/// assert_eq!(v.lower_bound_lenient(&9, 1), Err(8));
+/// ```
+pub trait LenientBound {
+    type Item;
+
+    fn lower_bound_lenient(&self, x: &Self::Item, k: usize) -> Result<usize, usize>;
+
+    fn upper_bound_lenient(&self, x: &Self::Item, k: usize) -> Result<usize, usize>;
+
+    /// The smallest `k` for which the lenient bounds are exact on this data,
+    /// the largest distance between an element and its position in a stable sort.
+    fn disorder(&self) -> usize;
+}
+
+impl<T: Ord> LenientBound for [T] {
+    type Item = T;
+
+    fn lower_bound_lenient(&self, x: &T, k: usize) -> Result<usize, usize> {
+        // The binary search stops at some p with a[p - 1] < x <= a[p], and any such p lies within
+        // k of the true count c. Everything before c - k is less than x and everything from
+        // c + k on is not, so only [p - 2k, p + 2k) needs looking at.
+        let p = self.lower_bound(x).unwrap_or_else(|e| e);
+        let lo = p.saturating_sub(2 * k);
+        let hi = p.saturating_add(2 * k).min(self.len());
+        let c = lo + self[lo..hi].iter().filter(|&e| e < x).count();
+        if c == self.len() {
+            Err(c)
+        } else {
+            Ok(c)
+        }
+    }
+
+    fn upper_bound_lenient(&self, x: &T, k: usize) -> Result<usize, usize> {
+        let p = self.upper_bound(x).unwrap_or_else(|e| e);
+        let lo = p.saturating_sub(2 * k);
+        let hi = p.saturating_add(2 * k).min(self.len());
+        let c = lo + self[lo..hi].iter().filter(|&e| e <= x).count();
+        if c == self.len() {
+            Err(c)
+        } else {
+            Ok(c)
+        }
+    }
+
+    fn disorder(&self) -> usize {
+        let mut order = (0..self.len()).collect::<Vec<_>>();
+        order.sort_by(|&i, &j| self[i].cmp(&self[j]));
+        order
+            .iter()
+            .enumerate()
+            .map(|(rank, &i)| rank.abs_diff(i))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::{LenientBound, LowerBound, UpperBound};
+
+    #[test]
+    fn test_lenient_bound() {
+        // disturb a sorted array by swapping elements a few positions apart
+        let sorted = (0..200).map(|x| x / 2).collect::<Vec<i32>>();
+        let mut v = sorted.clone();
+        let mut seed = 7u32;
+        for i in (0..v.len()).step_by(3) {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            let j = (i + (seed >> 16) as usize % 5).min(v.len() - 1);
+            v.swap(i, j);
+        }
+        let k = v.disorder();
+        assert!(k > 0);
+        for x in -1..102 {
+            assert_eq!(v.lower_bound_lenient(&x, k), sorted.lower_bound(&x), "x = {x}");
+            assert_eq!(v.upper_bound_lenient(&x, k), sorted.upper_bound(&x), "x = {x}");
+        }
+        assert_eq!(sorted.disorder(), 0);
+        assert_eq!(Vec::<i32>::new().lower_bound_lenient(&0, 4), Err(0));
+    }
+}
@@ -1,8 +1,136 @@
 #![doc = include_str!("../readme.md")]
 
 use std::collections::*;
+use std::ops::{Bound, Range, RangeBounds};
 use std::{cmp::Ordering, result::Result};
 
+pub mod extsort;
+pub mod keyenc;
+
+mod bits;
+mod trace;
+
+mod timerange;
+pub use timerange::*;
+
+mod position;
+pub use position::*;
+
+mod strided;
+pub use strided::*;
+
+mod lenient;
+pub use lenient::*;
+
+mod deref;
+pub use deref::*;
+
+mod explain;
+pub use explain::*;
+
+mod secondary;
+pub use secondary::*;
+
+mod multi_index;
+pub use multi_index::*;
+
+mod monotonic;
+pub use monotonic::*;
+
+mod argsort;
+pub use argsort::*;
+
+mod approx;
+pub use approx::*;
+
+mod wavelet;
+pub use wavelet::*;
+
+mod range_tree;
+pub use range_tree::*;
+
+mod prefilter;
+pub use prefilter::*;
+
+mod flat_multimap;
+pub use flat_multimap::*;
+
+mod rle_multiset;
+pub use rle_multiset::*;
+
+mod interner;
+pub use interner::*;
+
+mod louds;
+pub use louds::*;
+
+mod digitize;
+pub use digitize::*;
+
+mod interp;
+pub use interp::*;
+
+mod step_map;
+pub use step_map::*;
+
+mod expiry;
+pub use expiry::*;
+
+mod entry;
+pub use entry::*;
+
+mod patch;
+pub use patch::*;
+
+mod stable;
+pub use stable::*;
+
+mod resift;
+pub use resift::*;
+
+mod zorder;
+pub use zorder::*;
+
+mod order_book;
+pub use order_book::*;
+
+mod static_map;
+pub use static_map::*;
+
+mod validate;
+pub use validate::*;
+
+mod cached;
+pub use cached::*;
+
+mod guard;
+
+mod cyclic;
+pub use cyclic::*;
+
+mod natural;
+pub use natural::*;
+
+#[cfg(feature = "rayon")]
+mod par;
+#[cfg(feature = "rayon")]
+pub use par::*;
+
+#[cfg(feature = "arrow")]
+mod arrow;
+#[cfg(feature = "arrow")]
+pub use arrow::*;
+
+#[cfg(feature = "semver")]
+mod semver_range;
+#[cfg(feature = "semver")]
+pub use semver_range::*;
+
+#[cfg(feature = "csv")]
+mod csv_index;
+#[cfg(feature = "csv")]
+pub use csv_index::*;
+
 /// find first index where arr[idx] >= v; assume arr is sorted.
 /// it is a encapsulation of `position` method, like `position(|e| e.cmp(x) != Ordering::Less)`,
 /// but it returns `Err` when all elements are less than `x`.
@@ -159,6 +287,62 @@ impl<T: Ord> UpperBound for Vec<T> {
     }
 }
 
+/// With the `rkyv` feature, an archived `Vec` is searched right in the archive bytes.
+/// ```
+/// # use bound_stl::{LowerBound, UpperBound};
+/// # use rkyv::{rancor::Error, vec::ArchivedVec, Archived};
+/// let bytes = rkyv::to_bytes::<Error>(&vec![10u32, 20, 20, 30])?;
+/// let archived = rkyv::access::<ArchivedVec<Archived<u32>>, Error>(&bytes)?;
+/// assert_eq!(archived.lower_bound(&20.into()), Ok(1));
+/// assert_eq!(archived.upper_bound_by_key(&20, |x| x.to_native()), Ok(3));
+/// # Ok::<(), Error>(())
+/// ```
+#[cfg(feature = "rkyv")]
+impl<T: Ord> LowerBound for rkyv::vec::ArchivedVec<T> {
+    type Item = T;
+    fn lower_bound(&self, x: &Self::Item) -> Result<usize, usize> {
+        self.as_slice().lower_bound(x)
+    }
+
+    fn lower_bound_by<'a, F>(&'a self, f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> Ordering,
+    {
+        self.as_slice().lower_bound_by(f)
+    }
+
+    fn lower_bound_by_key<'a, K, F>(&'a self, k: &K, f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> K,
+        K: Ord,
+    {
+        self.as_slice().lower_bound_by_key(k, f)
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl<T: Ord> UpperBound for rkyv::vec::ArchivedVec<T> {
+    type Item = T;
+    fn upper_bound(&self, x: &Self::Item) -> Result<usize, usize> {
+        self.as_slice().upper_bound(x)
+    }
+
+    fn upper_bound_by<'a, F>(&'a self, f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> Ordering,
+    {
+        self.as_slice().upper_bound_by(f)
+    }
+
+    fn upper_bound_by_key<'a, K, F>(&'a self, k: &K, f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> K,
+        K: Ord,
+    {
+        self.as_slice().upper_bound_by_key(k, f)
+    }
+}
+
 /// To use `lower_bound` and `upper_bound` on `VecDeque`,
 /// you need to call `VecDeque::make_contiguous` first and sort it.
 /// ```
@@ -351,6 +535,68 @@ impl<T: Ord> UpperBound for BTreeSet<T> {
     }
 }
 
+/// `lower_bound_by` over positions `0..len` for containers that can't lend out `&Item`,
+/// `f(i)` compares the element at position `i` against the target.
+pub(crate) fn lower_bound_index<F>(len: usize, mut f: F) -> Result<usize, usize>
+where
+    F: FnMut(usize) -> Ordering,
+{
+    let (mut left, mut right) = (0, len);
+    while left < right {
+        let mid = left + (right - left) / 2;
+        match f(mid) {
+            Ordering::Less => left = mid + 1,
+            _ => right = mid,
+        }
+    }
+    if left == len {
+        Err(left)
+    } else {
+        Ok(left)
+    }
+}
+
+/// `upper_bound_by` over positions `0..len`, see [`lower_bound_index`].
+pub(crate) fn upper_bound_index<F>(len: usize, mut f: F) -> Result<usize, usize>
+where
+    F: FnMut(usize) -> Ordering,
+{
+    let (mut left, mut right) = (0, len);
+    while left < right {
+        let mid = left + (right - left) / 2;
+        match f(mid) {
+            Ordering::Greater => right = mid,
+            _ => left = mid + 1,
+        }
+    }
+    if left == len {
+        Err(left)
+    } else {
+        Ok(left)
+    }
+}
+
+/// Positions `0..len` whose elements lie in `range`, see [`lower_bound_index`];
+/// `f(i, k)` compares the element at position `i` against the bound `k`.
+pub(crate) fn range_index<K, R, F>(len: usize, range: R, mut f: F) -> Range<usize>
+where
+    R: RangeBounds<K>,
+    F: FnMut(usize, &K) -> Ordering,
+{
+    let start = match range.start_bound() {
+        Bound::Included(k) => lower_bound_index(len, |i| f(i, k)),
+        Bound::Excluded(k) => upper_bound_index(len, |i| f(i, k)),
+        Bound::Unbounded => Ok(0),
+    };
+    let end = match range.end_bound() {
+        Bound::Included(k) => upper_bound_index(len, |i| f(i, k)),
+        Bound::Excluded(k) => lower_bound_index(len, |i| f(i, k)),
+        Bound::Unbounded => Ok(len),
+    };
+    let (start, end) = (start.unwrap_or_else(|e| e), end.unwrap_or_else(|e| e));
+    start..end.max(start)
+}
+
 #[cfg(test)]
 mod tests {
     // use super::*;
@@ -397,21 +643,22 @@ mod tests {
     #[test]
     fn test_lower_bound_btreeset() {
         use std::collections::BTreeSet;
+        // `BTreeSet::lower_bound` is an unstable std method, so name the trait's explicitly
         let v = BTreeSet::<i32>::new();
-        assert_eq!(v.lower_bound(&0), Err(0));
-        assert_eq!(v.lower_bound(&1), Err(0));
+        assert_eq!(LowerBound::lower_bound(&v, &0), Err(0));
+        assert_eq!(LowerBound::lower_bound(&v, &1), Err(0));
 
         let v = vec![1, 2, 4, 5, 5, 6, 6].into_iter().collect::<BTreeSet<_>>();
         assert_eq!(format!("{v:?}"), "{1, 2, 4, 5, 6}");
-        assert_eq!(v.lower_bound(&0), Ok(0));
-        assert_eq!(v.lower_bound(&1), Ok(0));
-        assert_eq!(v.lower_bound(&2), Ok(1));
-        assert_eq!(v.lower_bound(&3), Ok(2));
-        assert_eq!(v.lower_bound(&4), Ok(2));
-        assert_eq!(v.lower_bound(&5), Ok(3));
-        assert_eq!(v.lower_bound(&6), Ok(4));
-        assert_eq!(v.lower_bound(&7), Err(5));
-        assert_eq!(v.lower_bound(&8), Err(5));
-        assert_eq!(v.lower_bound(&9), Err(5));
+        assert_eq!(LowerBound::lower_bound(&v, &0), Ok(0));
+        assert_eq!(LowerBound::lower_bound(&v, &1), Ok(0));
+        assert_eq!(LowerBound::lower_bound(&v, &2), Ok(1));
+        assert_eq!(LowerBound::lower_bound(&v, &3), Ok(2));
+        assert_eq!(LowerBound::lower_bound(&v, &4), Ok(2));
+        assert_eq!(LowerBound::lower_bound(&v, &5), Ok(3));
+        assert_eq!(LowerBound::lower_bound(&v, &6), Ok(4));
+        assert_eq!(LowerBound::lower_bound(&v, &7), Err(5));
+        assert_eq!(LowerBound::lower_bound(&v, &8), Err(5));
+        assert_eq!(LowerBound::lower_bound(&v, &9), Err(5));
     }
 }
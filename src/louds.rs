@@ -0,0 +1,285 @@
+use crate::bits::{PackedInts, RankBits};
+use crate::upper_bound_index;
+use std::collections::VecDeque;
+use std::ops::Range;
+
+/// A static trie over a sorted set of byte strings, stored as a LOUDS bit vector.
+///
+/// Nodes are numbered in breadth-first order and each node is written as one `1` per child
+/// followed by a `0`, so the whole shape takes about two bits per node, plus one label byte
+/// and a terminal bit. Every node also records the rank of the first key below it, in
+/// `log2(len)` bits, which is what lets the queries answer with key indices in one step per
+/// byte: a key's index is its rank in sorted order, and `lower_bound` follows the `Result`
+/// convention of `LowerBound`.
+/// ```
+/// # use bound_stl::LoudsTrie;
+/// let trie = LoudsTrie::new(["tea", "ten", "to", "inn", "tea", "in"]);
+/// assert_eq!(trie.len(), 5);
+/// assert_eq!(trie.index_of("ten"), Some(3));
+/// assert!(!trie.contains("te"));
+/// assert_eq!(trie.lower_bound("te"), Ok(2));
+/// assert_eq!(trie.lower_bound("u"), Err(5));
+/// assert_eq!(trie.prefix_count("te"), 2);
+/// let te = trie.prefix_iter("te").collect::<Vec<_>>();
+/// assert_eq!(te, [b"tea".to_vec(), b"ten".to_vec()]);
+/// assert_eq!(trie.key(4), Some(b"to".to_vec()));
+/// ```
+#[derive(Debug, Clone)]
+pub struct LoudsTrie {
+    louds: RankBits,
+    /// `labels[v - 1]`: the byte on the edge into node `v`
+    labels: Vec<u8>,
+    terminal: RankBits,
+    /// `first_key.get(v)`: rank of the smallest key in the subtree of `v`
+    first_key: PackedInts,
+    len: usize,
+}
+
+impl LoudsTrie {
+    pub fn new<I, S>(keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<[u8]>,
+    {
+        let mut keys = keys.into_iter().collect::<Vec<_>>();
+        keys.sort_unstable_by(|a, b| a.as_ref().cmp(b.as_ref()));
+        keys.dedup_by(|a, b| a.as_ref() == b.as_ref());
+        let key = |i: usize| keys[i].as_ref();
+
+        let mut louds = Vec::new();
+        let mut labels = Vec::new();
+        let mut terminal = Vec::new();
+        let mut first_key = Vec::new();
+        // breadth-first over (key range, depth); the keys of a range share their first `depth` bytes
+        let mut queue = VecDeque::from([(0..keys.len(), 0)]);
+        while let Some((range, depth)) = queue.pop_front() {
+            first_key.push(range.start);
+            let is_terminal = !range.is_empty() && key(range.start).len() == depth;
+            terminal.push(is_terminal);
+            let mut lo = range.start + is_terminal as usize;
+            while lo < range.end {
+                let b = key(lo)[depth];
+                let hi = lo + keys[lo..range.end].partition_point(|k| k.as_ref()[depth] == b);
+                louds.push(true);
+                labels.push(b);
+                queue.push_back((lo..hi, depth + 1));
+                lo = hi;
+            }
+            louds.push(false);
+        }
+        LoudsTrie {
+            louds: RankBits::new(louds.into_iter()),
+            labels,
+            terminal: RankBits::new(terminal.into_iter()),
+            first_key: PackedInts::new(first_key.into_iter(), keys.len()),
+            len: keys.len(),
+        }
+    }
+
+    /// Number of keys.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.labels.len() + 1
+    }
+
+    pub fn contains<K: AsRef<[u8]> + ?Sized>(&self, key: &K) -> bool {
+        self.index_of(key).is_some()
+    }
+
+    /// Rank of `key` among the keys, if present.
+    pub fn index_of<K: AsRef<[u8]> + ?Sized>(&self, key: &K) -> Option<usize> {
+        let (v, _) = self.walk(key.as_ref())?;
+        self.terminal.get(v).then(|| self.first_key.get(v))
+    }
+
+    /// The key of rank `idx`.
+    pub fn key(&self, idx: usize) -> Option<Vec<u8>> {
+        if idx >= self.len {
+            return None;
+        }
+        let mut out = Vec::new();
+        let mut v = 0;
+        while !(self.terminal.get(v) && self.first_key.get(v) == idx) {
+            // the last child whose first key is not past `idx`
+            let children = self.children(v);
+            let after = upper_bound_index(children.len(), |i| self.first_key.get(children.start + i).cmp(&idx));
+            let w = children.start + after.unwrap_or_else(|e| e) - 1;
+            out.push(self.labels[w - 1]);
+            v = w;
+        }
+        Some(out)
+    }
+
+    /// Rank of the first key not less than `key`.
+    pub fn lower_bound<K: AsRef<[u8]> + ?Sized>(&self, key: &K) -> Result<usize, usize> {
+        self.bound(key.as_ref(), false)
+    }
+
+    /// Rank of the first key greater than `key`.
+    pub fn upper_bound<K: AsRef<[u8]> + ?Sized>(&self, key: &K) -> Result<usize, usize> {
+        self.bound(key.as_ref(), true)
+    }
+
+    /// Ranks of the keys starting with `prefix`.
+    pub fn prefix_range<K: AsRef<[u8]> + ?Sized>(&self, prefix: &K) -> Range<usize> {
+        match self.walk(prefix.as_ref()) {
+            Some((v, end)) => self.first_key.get(v)..end,
+            None => {
+                let pos = self.lower_bound(prefix).unwrap_or_else(|e| e);
+                pos..pos
+            }
+        }
+    }
+
+    pub fn prefix_count<K: AsRef<[u8]> + ?Sized>(&self, prefix: &K) -> usize {
+        self.prefix_range(prefix).len()
+    }
+
+    /// The keys starting with `prefix`, in order.
+    pub fn prefix_iter<K: AsRef<[u8]> + ?Sized>(&self, prefix: &K) -> impl Iterator<Item = Vec<u8>> + '_ {
+        let prefix = prefix.as_ref();
+        let root = self.walk(prefix).map(|(v, _)| v);
+        let mut stack = root.into_iter().map(|v| (v, prefix.len())).collect::<Vec<_>>();
+        let mut key = prefix.to_vec();
+        std::iter::from_fn(move || {
+            while let Some((v, depth)) = stack.pop() {
+                if Some(v) != root {
+                    key.truncate(depth - 1);
+                    key.push(self.labels[v - 1]);
+                }
+                stack.extend(self.children(v).rev().map(|w| (w, depth + 1)));
+                if self.terminal.get(v) {
+                    return Some(key.clone());
+                }
+            }
+            None
+        })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Vec<u8>> + '_ {
+        self.prefix_iter(&[])
+    }
+
+    /// Node ids of the children of `v`, in label order.
+    fn children(&self, v: usize) -> Range<usize> {
+        let start = if v == 0 { 0 } else { self.louds.select0(v - 1) + 1 };
+        let end = self.louds.select0(v);
+        let first = self.louds.rank1(start) + 1;
+        first..first + (end - start)
+    }
+
+    /// The child of `v` labelled `b`, or else the first child with a greater label.
+    fn child(&self, v: usize, b: u8) -> Result<usize, usize> {
+        let children = self.children(v);
+        let labels = &self.labels[children.start - 1..children.end - 1];
+        let w = children.start + labels.partition_point(|&l| l < b);
+        if w < children.end && self.labels[w - 1] == b {
+            Ok(w)
+        } else {
+            Err(w)
+        }
+    }
+
+    /// The node spelling `path`, with the end of its key range.
+    fn walk(&self, path: &[u8]) -> Option<(usize, usize)> {
+        let (mut v, mut end) = (0, self.len);
+        for &b in path {
+            let w = self.child(v, b).ok()?;
+            end = self.subtree_end(v, w, end);
+            v = w;
+        }
+        Some((v, end))
+    }
+
+    /// End of the key range of child `w`, given the end of its parent `v`.
+    fn subtree_end(&self, v: usize, w: usize, parent_end: usize) -> usize {
+        if w + 1 < self.children(v).end {
+            self.first_key.get(w + 1)
+        } else {
+            parent_end
+        }
+    }
+
+    fn bound(&self, key: &[u8], upper: bool) -> Result<usize, usize> {
+        let (mut v, mut end) = (0, self.len);
+        let mut pos = None;
+        for &b in key {
+            match self.child(v, b) {
+                Ok(w) => {
+                    end = self.subtree_end(v, w, end);
+                    v = w;
+                }
+                Err(w) => {
+                    // every key below `v` that sorts before `w` is less than `key`
+                    pos = Some(if w < self.children(v).end {
+                        self.first_key.get(w)
+                    } else {
+                        end
+                    });
+                    break;
+                }
+            }
+        }
+        let pos = pos.unwrap_or_else(|| self.first_key.get(v) + (upper && self.terminal.get(v)) as usize);
+        if pos == self.len {
+            Err(pos)
+        } else {
+            Ok(pos)
+        }
+    }
+}
+
+impl<S: AsRef<[u8]>> FromIterator<S> for LoudsTrie {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        LoudsTrie::new(iter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::{LoudsTrie, LowerBound, UpperBound};
+
+    #[test]
+    fn test_louds_trie() {
+        let mut words = [
+            "", "a", "ab", "abc", "abd", "b", "ba", "bab", "c", "ca", "cab", "caba", "d",
+        ]
+        .iter()
+        .map(|w| w.as_bytes().to_vec())
+        .collect::<Vec<_>>();
+        // enough fan-out to span several LOUDS words
+        words.extend((0..300u32).map(|i| format!("x{i:03}").into_bytes()));
+        let trie = words.iter().rev().collect::<LoudsTrie>();
+        words.sort();
+        assert_eq!(trie.len(), words.len());
+        assert!(trie.iter().eq(words.iter().cloned()));
+        for (i, w) in words.iter().enumerate() {
+            assert_eq!(trie.index_of(w), Some(i));
+            assert_eq!(trie.key(i).as_ref(), Some(w));
+        }
+        assert_eq!(trie.key(words.len()), None);
+        let queries = [
+            "", "a", "aa", "abb", "abz", "b", "bb", "caa", "cabz", "e", "x1", "x150", "x2999", "y",
+        ];
+        for q in queries.map(str::as_bytes) {
+            let q = q.to_vec();
+            assert_eq!(trie.lower_bound(&q), words.lower_bound(&q), "q = {q:?}");
+            assert_eq!(trie.upper_bound(&q), words.upper_bound(&q), "q = {q:?}");
+            let expect = words.iter().filter(|w| w.starts_with(&q)).cloned().collect::<Vec<_>>();
+            assert_eq!(trie.prefix_count(&q), expect.len(), "q = {q:?}");
+            assert!(trie.prefix_iter(&q).eq(expect), "q = {q:?}");
+        }
+
+        let empty = LoudsTrie::new(Vec::<String>::new());
+        assert!(empty.is_empty());
+        assert_eq!(empty.lower_bound("a"), Err(0));
+        assert_eq!(empty.iter().count(), 0);
+    }
+}
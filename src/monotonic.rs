@@ -0,0 +1,164 @@
+use std::collections::VecDeque;
+
+/// A deque that keeps only the candidates for the minimum (or maximum) of a sliding window,
+/// giving the window's extreme in O(1) amortized time per pushed element.
+///
+/// Values are pushed at the back; every pushed value gets a sequence number, counting from 0.
+/// The front is always the extreme of the values pushed since the last eviction. Old values
+/// leave either by window size ([`with_window`](Self::with_window), [`evict_before`](Self::evict_before))
+/// or by a predicate ([`evict_while`](Self::evict_while)), e.g. on a timestamp stored with the value.
+/// ```
+/// # use bound_stl::MonotonicDeque;
+/// let data = [4, 2, 12, 3, 8, 1, 7];
+/// let mut window = MonotonicDeque::new_min().with_window(3);
+/// let mins = data
+///     .iter()
+///     .filter_map(|&x| {
+///         window.push(x);
+///         (window.pushed() >= 3).then(|| *window.front().unwrap())
+///     })
+///     .collect::<Vec<_>>();
+/// assert_eq!(mins, vec![2, 2, 3, 1, 1]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MonotonicDeque<T> {
+    items: VecDeque<(u64, T)>,
+    next: u64,
+    keep_max: bool,
+    window: Option<u64>,
+}
+
+impl<T: Ord> MonotonicDeque<T> {
+    /// A deque whose front is the minimum.
+    pub fn new_min() -> Self {
+        Self::new(false)
+    }
+
+    /// A deque whose front is the maximum.
+    pub fn new_max() -> Self {
+        Self::new(true)
+    }
+
+    fn new(keep_max: bool) -> Self {
+        MonotonicDeque {
+            items: VecDeque::new(),
+            next: 0,
+            keep_max,
+            window: None,
+        }
+    }
+
+    /// Only the last `size` pushed values take part; older ones are evicted on `push`.
+    pub fn with_window(mut self, size: usize) -> Self {
+        assert!(size > 0, "window size must not be zero");
+        self.window = Some(size as u64);
+        self
+    }
+
+    /// Pushes a value and returns its sequence number.
+    pub fn push(&mut self, value: T) -> u64 {
+        while let Some((_, back)) = self.items.back() {
+            let dominated = if self.keep_max { *back <= value } else { *back >= value };
+            if !dominated {
+                break;
+            }
+            self.items.pop_back();
+        }
+        let seq = self.next;
+        self.items.push_back((seq, value));
+        self.next += 1;
+        if let Some(size) = self.window {
+            self.evict_before(self.next.saturating_sub(size));
+        }
+        seq
+    }
+
+    /// The minimum (or maximum) of the current window.
+    pub fn front(&self) -> Option<&T> {
+        self.items.front().map(|(_, v)| v)
+    }
+
+    /// Sequence number of the value at the front.
+    pub fn front_seq(&self) -> Option<u64> {
+        self.items.front().map(|&(seq, _)| seq)
+    }
+
+    /// Evicts every value pushed before sequence number `seq`.
+    pub fn evict_before(&mut self, seq: u64) {
+        while self.items.front().is_some_and(|&(s, _)| s < seq) {
+            self.items.pop_front();
+        }
+    }
+
+    /// Evicts candidates from the front for as long as `pred` holds.
+    /// The candidates are in push order, so with `pred` testing a timestamp stored in the
+    /// value this drops everything older than a cut-off.
+    pub fn evict_while<F: FnMut(&T) -> bool>(&mut self, mut pred: F) {
+        while self.items.front().is_some_and(|(_, v)| pred(v)) {
+            self.items.pop_front();
+        }
+    }
+
+    /// Total number of values pushed so far, i.e. the next sequence number.
+    pub fn pushed(&self) -> u64 {
+        self.next
+    }
+
+    /// Number of candidates currently kept, not the window size.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Drops all candidates; sequence numbers keep counting.
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+
+    /// The candidates from front to back, monotone in value and in push order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        self.items.iter().map(|(_, v)| v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::MonotonicDeque;
+
+    #[test]
+    fn test_monotonic_deque_window() {
+        let data = [5, 1, 4, 4, 9, 2, 6, 6, 3, 8, 0, 7];
+        for w in 1..5 {
+            let mut min = MonotonicDeque::new_min().with_window(w);
+            let mut max = MonotonicDeque::new_max().with_window(w);
+            for (i, &x) in data.iter().enumerate() {
+                min.push(x);
+                max.push(x);
+                let window = &data[(i + 1).saturating_sub(w)..=i];
+                assert_eq!(min.front(), window.iter().min());
+                assert_eq!(max.front(), window.iter().max());
+            }
+        }
+    }
+
+    #[test]
+    fn test_monotonic_deque_evict() {
+        // (value, timestamp) pairs, evicted by age
+        let mut d = MonotonicDeque::new_max();
+        for (t, v) in [(0, 3), (1, 9), (2, 4), (5, 6), (6, 1)] {
+            d.push((v, t));
+        }
+        assert_eq!(d.front(), Some(&(9, 1)));
+        d.evict_while(|&(_, t)| t < 2);
+        assert_eq!(d.front(), Some(&(6, 5)));
+        assert_eq!(d.iter().count(), 2);
+
+        assert_eq!(d.front_seq(), Some(3));
+        d.evict_before(5);
+        assert!(d.is_empty());
+        assert_eq!(d.push((0, 7)), 5);
+    }
+}
@@ -0,0 +1,402 @@
+use crate::{lower_bound_index, range_index, upper_bound_index};
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::RangeBounds;
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
+use std::sync::Arc;
+
+type KeyFn<T, K> = Arc<dyn Fn(&T) -> K + Send + Sync>;
+type CmpFn<T> = Arc<dyn Fn(&T, &T) -> Ordering + Send + Sync>;
+
+/// Identifies a record of a [`MultiIndex`]; stays valid until the record is removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RecordId {
+    slot: usize,
+    generation: u64,
+}
+
+/// Names one sorted view of a [`MultiIndex`], returned by [`MultiIndex::add_view`].
+pub struct ViewId<T, K> {
+    /// the [`MultiIndex::owner`] of the index it belongs to
+    owner: u64,
+    id: usize,
+    key: KeyFn<T, K>,
+}
+
+impl<T, K> Clone for ViewId<T, K> {
+    fn clone(&self) -> Self {
+        ViewId {
+            owner: self.owner,
+            id: self.id,
+            key: self.key.clone(),
+        }
+    }
+}
+
+impl<T, K> fmt::Debug for ViewId<T, K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ViewId").field("id", &self.id).finish()
+    }
+}
+
+struct Slot<T> {
+    generation: u64,
+    value: Option<T>,
+}
+
+struct Order<T> {
+    cmp: CmpFn<T>,
+    /// slot numbers, sorted by `cmp` and then by slot
+    slots: Vec<usize>,
+}
+
+/// A container that owns its records once and keeps several sorted views over them,
+/// each ordered by its own key, in the spirit of Boost.MultiIndex.
+///
+/// Every mutation goes through the container, so the views can never disagree: `insert`,
+/// `remove` and `modify` update all of them. Views are queried by key with
+/// [`View::lower_bound`], [`View::upper_bound`] and [`View::range`], positions are ranks
+/// within that view.
+/// ```
+/// # use bound_stl::MultiIndex;
+/// struct User { id: u32, name: &'static str, age: u8 }
+///
+/// let mut users = MultiIndex::new();
+/// let by_id = users.add_view(|u: &User| u.id);
+/// let by_age = users.add_view(|u: &User| u.age);
+///
+/// users.insert(User { id: 7, name: "ann", age: 41 });
+/// let bob = users.insert(User { id: 3, name: "bob", age: 29 });
+/// users.insert(User { id: 5, name: "cid", age: 35 });
+///
+/// let names = users.view(&by_id).iter().map(|(_, u)| u.name).collect::<Vec<_>>();
+/// assert_eq!(names, vec!["bob", "cid", "ann"]);
+/// let over_30 = users.view(&by_age).range(30..).map(|(_, u)| u.name).collect::<Vec<_>>();
+/// assert_eq!(over_30, vec!["cid", "ann"]);
+///
+/// users.modify(bob, |u| u.age = 50);
+/// assert_eq!(users.view(&by_age).lower_bound(&45), Ok(2));
+/// assert_eq!(users.view(&by_id).find(&3).map(|(_, u)| u.age), Some(50));
+/// ```
+pub struct MultiIndex<T> {
+    /// tells the views of this index from those of other indexes
+    owner: u64,
+    slots: Vec<Slot<T>>,
+    free: Vec<usize>,
+    orders: Vec<Order<T>>,
+    len: usize,
+}
+
+impl<T> Default for MultiIndex<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> MultiIndex<T> {
+    pub fn new() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        MultiIndex {
+            owner: NEXT.fetch_add(1, AtomicOrdering::Relaxed),
+            slots: Vec::new(),
+            free: Vec::new(),
+            orders: Vec::new(),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Adds a view ordered by `key`; records already present are indexed right away.
+    pub fn add_view<K, F>(&mut self, key: F) -> ViewId<T, K>
+    where
+        T: 'static,
+        K: Ord + 'static,
+        F: Fn(&T) -> K + Send + Sync + 'static,
+    {
+        let key: KeyFn<T, K> = Arc::new(key);
+        let k = key.clone();
+        let cmp: CmpFn<T> = Arc::new(move |a, b| k(a).cmp(&k(b)));
+        let mut slots = (0..self.slots.len())
+            .filter(|&s| self.slots[s].value.is_some())
+            .collect::<Vec<_>>();
+        slots.sort_by(|&a, &b| self.compare(&cmp, a, b));
+        self.orders.push(Order { cmp, slots });
+        ViewId {
+            owner: self.owner,
+            id: self.orders.len() - 1,
+            key,
+        }
+    }
+
+    /// Panics if `view` was added to another `MultiIndex`.
+    pub fn view<'a, K: Ord>(&'a self, view: &'a ViewId<T, K>) -> View<'a, T, K> {
+        assert_eq!(view.owner, self.owner, "view of another multi index");
+        View {
+            index: self,
+            slots: &self.orders[view.id].slots,
+            key: &*view.key,
+        }
+    }
+
+    pub fn get(&self, id: RecordId) -> Option<&T> {
+        self.slots
+            .get(id.slot)
+            .filter(|s| s.generation == id.generation)
+            .and_then(|s| s.value.as_ref())
+    }
+
+    pub fn contains(&self, id: RecordId) -> bool {
+        self.get(id).is_some()
+    }
+
+    /// Records in insertion-slot order, which is not any view's order.
+    pub fn iter(&self) -> impl Iterator<Item = (RecordId, &T)> {
+        self.slots.iter().enumerate().filter_map(|(slot, s)| {
+            let id = RecordId {
+                slot,
+                generation: s.generation,
+            };
+            s.value.as_ref().map(|v| (id, v))
+        })
+    }
+
+    pub fn insert(&mut self, value: T) -> RecordId {
+        let slot = match self.free.pop() {
+            Some(slot) => {
+                self.slots[slot].value = Some(value);
+                slot
+            }
+            None => {
+                self.slots.push(Slot {
+                    generation: 0,
+                    value: Some(value),
+                });
+                self.slots.len() - 1
+            }
+        };
+        self.link(slot);
+        self.len += 1;
+        RecordId {
+            slot,
+            generation: self.slots[slot].generation,
+        }
+    }
+
+    pub fn remove(&mut self, id: RecordId) -> Option<T> {
+        self.get(id)?;
+        self.unlink(id.slot);
+        self.release(id.slot)
+    }
+
+    /// Changes a record in place and moves it to its new position in every view.
+    /// Returns `false` if the record does not exist. If `f` panics, the record is removed.
+    pub fn modify<F: FnOnce(&mut T)>(&mut self, id: RecordId, f: F) -> bool {
+        if self.get(id).is_none() {
+            return false;
+        }
+        self.unlink(id.slot);
+        let guard = Unlinked {
+            index: self,
+            slot: id.slot,
+        };
+        f(guard.index.slots[id.slot].value.as_mut().unwrap());
+        std::mem::forget(guard);
+        self.link(id.slot);
+        true
+    }
+
+    pub fn clear(&mut self) {
+        self.free
+            .extend((0..self.slots.len()).filter(|&s| self.slots[s].value.is_some()));
+        for s in self.slots.iter_mut().filter(|s| s.value.is_some()) {
+            s.value = None;
+            s.generation += 1;
+        }
+        self.orders.iter_mut().for_each(|o| o.slots.clear());
+        self.len = 0;
+    }
+
+    /// Frees the slot of a record that is in no view.
+    fn release(&mut self, slot: usize) -> Option<T> {
+        self.free.push(slot);
+        self.len -= 1;
+        let slot = &mut self.slots[slot];
+        slot.generation += 1;
+        slot.value.take()
+    }
+
+    fn value(&self, slot: usize) -> &T {
+        self.slots[slot].value.as_ref().unwrap()
+    }
+
+    fn compare(&self, cmp: &CmpFn<T>, a: usize, b: usize) -> Ordering {
+        cmp(self.value(a), self.value(b)).then(a.cmp(&b))
+    }
+
+    fn position(&self, order: usize, slot: usize) -> Result<usize, usize> {
+        let o = &self.orders[order];
+        o.slots.binary_search_by(|&s| self.compare(&o.cmp, s, slot))
+    }
+
+    fn link(&mut self, slot: usize) {
+        for order in 0..self.orders.len() {
+            let pos = self.position(order, slot).unwrap_or_else(|e| e);
+            self.orders[order].slots.insert(pos, slot);
+        }
+    }
+
+    fn unlink(&mut self, slot: usize) {
+        for order in 0..self.orders.len() {
+            let pos = self.position(order, slot).expect("multi index view out of sync");
+            self.orders[order].slots.remove(pos);
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for MultiIndex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// Removes a record whose `modify` closure panicked, as it is no longer in any view.
+struct Unlinked<'a, T> {
+    index: &'a mut MultiIndex<T>,
+    slot: usize,
+}
+
+impl<T> Drop for Unlinked<'_, T> {
+    fn drop(&mut self) {
+        self.index.release(self.slot);
+    }
+}
+
+/// One sorted view of a [`MultiIndex`], see [`MultiIndex::view`].
+pub struct View<'a, T, K> {
+    index: &'a MultiIndex<T>,
+    slots: &'a [usize],
+    key: &'a (dyn Fn(&T) -> K + Send + Sync),
+}
+
+impl<'a, T, K: Ord> View<'a, T, K> {
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// The record at rank `pos` in this view.
+    pub fn get(&self, pos: usize) -> Option<(RecordId, &'a T)> {
+        self.slots.get(pos).map(|&s| self.entry(s))
+    }
+
+    /// First record whose key equals `k`.
+    pub fn find(&self, k: &K) -> Option<(RecordId, &'a T)> {
+        let pos = self.lower_bound(k).ok()?;
+        Some(self.entry(self.slots[pos])).filter(|(_, v)| (self.key)(v) == *k)
+    }
+
+    pub fn lower_bound(&self, k: &K) -> Result<usize, usize> {
+        self.lower_bound_by(|v| (self.key)(v).cmp(k))
+    }
+
+    pub fn upper_bound(&self, k: &K) -> Result<usize, usize> {
+        self.upper_bound_by(|v| (self.key)(v).cmp(k))
+    }
+
+    pub fn lower_bound_by<F: FnMut(&'a T) -> Ordering>(&self, mut f: F) -> Result<usize, usize> {
+        lower_bound_index(self.len(), |i| f(self.index.value(self.slots[i])))
+    }
+
+    pub fn upper_bound_by<F: FnMut(&'a T) -> Ordering>(&self, mut f: F) -> Result<usize, usize> {
+        upper_bound_index(self.len(), |i| f(self.index.value(self.slots[i])))
+    }
+
+    /// Records in this view's order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (RecordId, &'a T)> + '_ {
+        self.slots.iter().map(|&s| self.entry(s))
+    }
+
+    /// Records whose key lies in `range`, in this view's order.
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> impl DoubleEndedIterator<Item = (RecordId, &'a T)> + '_ {
+        let span = range_index(self.len(), range, |i, k| {
+            (self.key)(self.index.value(self.slots[i])).cmp(k)
+        });
+        self.slots[span].iter().map(|&s| self.entry(s))
+    }
+
+    fn entry(&self, slot: usize) -> (RecordId, &'a T) {
+        let id = RecordId {
+            slot,
+            generation: self.index.slots[slot].generation,
+        };
+        (id, self.index.value(slot))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::MultiIndex;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    #[test]
+    fn test_multi_index() {
+        let mut mi = MultiIndex::new();
+        let a = mi.insert((3, "c"));
+        let b = mi.insert((1, "z"));
+        let by_num = mi.add_view(|r: &(i32, &str)| r.0);
+        let by_name = mi.add_view(|r: &(i32, &str)| r.1);
+        let c = mi.insert((2, "a"));
+        mi.insert((2, "b"));
+
+        let nums = mi.view(&by_num).iter().map(|(_, r)| r.0).collect::<Vec<_>>();
+        assert_eq!(nums, vec![1, 2, 2, 3]);
+        let names = mi.view(&by_name).iter().map(|(_, r)| r.1).collect::<Vec<_>>();
+        assert_eq!(names, vec!["a", "b", "c", "z"]);
+        assert_eq!(mi.view(&by_num).lower_bound(&2), Ok(1));
+        assert_eq!(mi.view(&by_num).upper_bound(&3), Err(4));
+        assert_eq!(mi.view(&by_num).range(2..=2).count(), 2);
+
+        assert_eq!(mi.remove(a), Some((3, "c")));
+        assert_eq!(mi.remove(a), None);
+        assert!(!mi.modify(a, |r| r.0 = 0));
+        assert!(mi.modify(c, |r| *r = (9, "y")));
+        let names = mi.view(&by_name).iter().map(|(_, r)| r.1).collect::<Vec<_>>();
+        assert_eq!(names, vec!["b", "y", "z"]);
+        assert_eq!(mi.view(&by_num).get(2).map(|(id, _)| id), Some(c));
+
+        // a reused slot gets a fresh id
+        let d = mi.insert((5, "d"));
+        assert_ne!(d, a);
+        assert_eq!(mi.get(d), Some(&(5, "d")));
+        assert_eq!(mi.get(b), Some(&(1, "z")));
+        assert_eq!(mi.len(), 4);
+        assert_eq!(mi.view(&by_name).find(&"d").map(|(id, _)| id), Some(d));
+        assert!(mi.view(&by_name).find(&"e").is_none());
+
+        // a panicking `modify` removes the record instead of leaving it out of the views
+        let res = catch_unwind(AssertUnwindSafe(|| mi.modify(d, |_| panic!("modify failed"))));
+        assert!(res.is_err());
+        assert!(!mi.contains(d));
+        assert_eq!(mi.len(), 3);
+        assert_eq!(mi.view(&by_name).len(), 3);
+        assert_eq!(mi.iter().count(), 3);
+
+        // views are bound to the index they were added to
+        let other = MultiIndex::<(i32, &str)>::new();
+        assert!(catch_unwind(AssertUnwindSafe(|| other.view(&by_num).len())).is_err());
+
+        mi.clear();
+        assert!(mi.is_empty() && mi.view(&by_num).is_empty());
+        assert!(mi.get(d).is_none());
+    }
+}
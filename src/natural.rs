@@ -0,0 +1,172 @@
+use std::cmp::Ordering;
+
+/// How [`NaturalOrder`] compares digit runs that start with `0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LeadingZeros {
+    /// By value: `"7"`, `"07"` and `"007"` are the same number.
+    #[default]
+    Numeric,
+    /// Digit by digit when either run starts with `0`, as the digits after a decimal point:
+    /// `"1.05" < "1.1" < "1.15"`.
+    Fraction,
+}
+
+/// A natural, numeric-aware string order: runs of ASCII digits compare as numbers, so
+/// `"file2" < "file10"`, everything else compares by `char`.
+///
+/// [`compare`](Self::compare) returns `Equal` for strings that differ only in what the options
+/// ignore, such as `"a7"` and `"a07"`; chain `.then_with(|| a.cmp(b))` for a total order, as
+/// [`Natural`] does. Numbers of any length are compared without parsing them.
+/// ```
+/// # use bound_stl::{LowerBound, NaturalOrder};
+/// let files = ["File1.txt", "file2.txt", "file10.txt", "File10b.txt", "file100.txt"];
+/// let order = NaturalOrder::new().case_insensitive(true);
+/// assert!(files.is_sorted_by(|a, b| order.compare(a, b).is_le()));
+/// assert_eq!(files.lower_bound_by(|f| order.compare(f, "FILE10.txt")), Ok(2));
+/// assert_eq!(files.lower_bound_by(|f| order.compare(f, "file11")), Ok(4));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct NaturalOrder {
+    case_insensitive: bool,
+    leading_zeros: LeadingZeros,
+}
+
+impl NaturalOrder {
+    /// Case-sensitive, with numeric leading zeros.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compares letters by their lowercase forms.
+    pub fn case_insensitive(mut self, yes: bool) -> Self {
+        self.case_insensitive = yes;
+        self
+    }
+
+    pub fn leading_zeros(mut self, leading_zeros: LeadingZeros) -> Self {
+        self.leading_zeros = leading_zeros;
+        self
+    }
+
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        let (mut a, mut b) = (a, b);
+        loop {
+            let (Some(ca), Some(cb)) = (a.chars().next(), b.chars().next()) else {
+                return a.len().cmp(&b.len());
+            };
+            if ca.is_ascii_digit() && cb.is_ascii_digit() {
+                let (da, ra) = a.split_at(a.find(|c: char| !c.is_ascii_digit()).unwrap_or(a.len()));
+                let (db, rb) = b.split_at(b.find(|c: char| !c.is_ascii_digit()).unwrap_or(b.len()));
+                let ord = match self.leading_zeros {
+                    LeadingZeros::Fraction if ca == '0' || cb == '0' => da.cmp(db),
+                    _ => {
+                        let (da, db) = (da.trim_start_matches('0'), db.trim_start_matches('0'));
+                        da.len().cmp(&db.len()).then_with(|| da.cmp(db))
+                    }
+                };
+                if ord.is_ne() {
+                    return ord;
+                }
+                (a, b) = (ra, rb);
+                continue;
+            }
+            let ord = if self.case_insensitive {
+                ca.to_lowercase().cmp(cb.to_lowercase())
+            } else {
+                ca.cmp(&cb)
+            };
+            if ord.is_ne() {
+                return ord;
+            }
+            (a, b) = (&a[ca.len_utf8()..], &b[cb.len_utf8()..]);
+        }
+    }
+}
+
+/// [`NaturalOrder::new`]'s comparison, for use with the `_by` methods.
+/// ```
+/// # use bound_stl::{natural_cmp, UpperBound};
+/// let pages = ["page1", "page2", "page10", "page10", "page11"];
+/// assert_eq!(pages.upper_bound_by(|p| natural_cmp(p, "page10")), Ok(4));
+/// ```
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    NaturalOrder::new().compare(a, b)
+}
+
+/// Wraps a string so that it sorts in natural order, for sorted containers and `_by_key`.
+///
+/// The order is [`natural_cmp`], with strings that compare equal there ordered by `str::cmp`,
+/// so it is a total order that agrees with `==`.
+/// ```
+/// # use bound_stl::{LowerBound, Natural};
+/// let mut v = vec!["v1.10", "v1.9", "v1.09", "v1.2"];
+/// v.sort_by_key(|s| Natural(*s));
+/// assert_eq!(v, ["v1.2", "v1.09", "v1.9", "v1.10"]);
+/// assert_eq!(v.lower_bound_by_key(&Natural("v1.3"), |s| Natural(*s)), Ok(1));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Natural<S>(pub S);
+
+impl<S: AsRef<str> + Eq> Ord for Natural<S> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (a, b) = (self.0.as_ref(), other.0.as_ref());
+        natural_cmp(a, b).then_with(|| a.cmp(b))
+    }
+}
+
+impl<S: AsRef<str> + Eq> PartialOrd for Natural<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::{natural_cmp, LeadingZeros, LowerBound, Natural, NaturalOrder, UpperBound};
+    use std::cmp::Ordering::*;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_natural_order() {
+        assert_eq!(natural_cmp("file2", "file10"), Less);
+        assert_eq!(natural_cmp("file10", "file10"), Equal);
+        assert_eq!(natural_cmp("a07", "a7"), Equal);
+        assert_eq!(natural_cmp("a007b", "a7c"), Less);
+        assert_eq!(natural_cmp("x", "x0"), Less);
+        assert_eq!(natural_cmp("9", "a"), Less);
+        assert_eq!(natural_cmp("B", "a"), Less);
+        assert_eq!(natural_cmp("2", "12345678901234567890123"), Less);
+        assert_eq!(natural_cmp("99999999999999999999999", "100000000000000000000000"), Less);
+        assert_eq!(natural_cmp("é2", "é10"), Less);
+
+        let folded = NaturalOrder::new().case_insensitive(true);
+        assert_eq!(folded.compare("B", "a"), Greater);
+        assert_eq!(folded.compare("Img12", "IMG12"), Equal);
+        assert_eq!(folded.compare("ÄX", "äy"), Less);
+
+        let fraction = NaturalOrder::new().leading_zeros(LeadingZeros::Fraction);
+        let mut v = vec!["1.15", "1.1", "1.05", "1.010"];
+        v.sort_by(|a, b| fraction.compare(a, b));
+        assert_eq!(v, ["1.010", "1.05", "1.1", "1.15"]);
+        assert_eq!(fraction.compare("x05", "x5"), Less);
+        assert_eq!(fraction.compare("x12", "x5"), Greater);
+
+        let names = ["img1", "img2", "IMG3", "img10", "img10", "img012", "Img20"];
+        assert!(names.is_sorted_by(|a, b| folded.compare(a, b).is_le()));
+        assert_eq!(names.lower_bound_by(|n| folded.compare(n, "img10")), Ok(3));
+        assert_eq!(names.upper_bound_by(|n| folded.compare(n, "img11")), Ok(5));
+        assert_eq!(names.upper_bound_by(|n| folded.compare(n, "img99")), Err(7));
+
+        let set = ["track10", "track9", "track09", "Track1"]
+            .into_iter()
+            .map(Natural)
+            .collect::<BTreeSet<_>>();
+        let sorted = set.into_iter().collect::<Vec<_>>();
+        assert!(sorted
+            .iter()
+            .map(|n| n.0)
+            .eq(["Track1", "track09", "track9", "track10"]));
+        assert_eq!(sorted.lower_bound(&Natural("track9")), Ok(2));
+        assert_ne!(Natural("a7"), Natural("a07"));
+    }
+}
@@ -0,0 +1,243 @@
+use crate::{lower_bound_index, upper_bound_index};
+use std::cmp::Ordering;
+use std::iter::Sum;
+use std::ops::AddAssign;
+
+/// A side of an [`OrderBook`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+    /// Buy orders, the highest price is best.
+    Bid,
+    /// Sell orders, the lowest price is best.
+    Ask,
+}
+
+impl Side {
+    /// Orders prices from worse to better on this side.
+    fn cmp<P: Ord>(self, a: &P, b: &P) -> Ordering {
+        match self {
+            Side::Bid => a.cmp(b),
+            Side::Ask => b.cmp(a),
+        }
+    }
+}
+
+/// Aggregated price levels of a limit order book, one sorted `Vec` per side.
+///
+/// Each side is kept worst price first, so the best level is at the end of its `Vec` and the
+/// busy top of the book is updated without shifting the rest. Indices taken or returned by the
+/// methods are depths instead: `0` is the best level of the side, counting away from the spread.
+/// ```
+/// # use bound_stl::{OrderBook, Side};
+/// let mut book = OrderBook::new();
+/// book.insert(Side::Bid, 99, 5);
+/// book.insert(Side::Bid, 100, 2);
+/// book.insert(Side::Bid, 100, 3);
+/// book.insert(Side::Ask, 101, 4);
+/// book.insert(Side::Ask, 103, 7);
+/// assert_eq!(book.best_bid(), Some((&100, &5)));
+/// assert_eq!(book.best_ask(), Some((&101, &4)));
+///
+/// // everything a buyer could take at up to 103
+/// assert_eq!(book.depth_at(Side::Ask, &103), 11);
+/// assert_eq!(book.lower_bound(Side::Bid, &99), Ok(1));
+///
+/// book.cancel(Side::Ask, &101);
+/// assert_eq!(book.best_ask(), Some((&103, &7)));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OrderBook<P, Q> {
+    bids: Vec<(P, Q)>,
+    asks: Vec<(P, Q)>,
+}
+
+impl<P, Q> Default for OrderBook<P, Q> {
+    fn default() -> Self {
+        OrderBook {
+            bids: Vec::new(),
+            asks: Vec::new(),
+        }
+    }
+}
+
+impl<P: Ord, Q> OrderBook<P, Q> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn clear(&mut self) {
+        self.bids.clear();
+        self.asks.clear();
+    }
+
+    /// Number of price levels on `side`.
+    pub fn len(&self, side: Side) -> usize {
+        self.side(side).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bids.is_empty() && self.asks.is_empty()
+    }
+
+    pub fn best_bid(&self) -> Option<(&P, &Q)> {
+        self.best(Side::Bid)
+    }
+
+    pub fn best_ask(&self) -> Option<(&P, &Q)> {
+        self.best(Side::Ask)
+    }
+
+    pub fn best(&self, side: Side) -> Option<(&P, &Q)> {
+        self.side(side).last().map(|(p, q)| (p, q))
+    }
+
+    /// Quantity resting at `price`.
+    pub fn level(&self, side: Side, price: &P) -> Option<&Q> {
+        let i = self.find(side, price).ok()?;
+        Some(&self.side(side)[i].1)
+    }
+
+    /// The level at `depth`, best first.
+    pub fn level_at(&self, side: Side, depth: usize) -> Option<(&P, &Q)> {
+        let levels = self.side(side);
+        let i = levels.len().checked_sub(depth + 1)?;
+        Some((&levels[i].0, &levels[i].1))
+    }
+
+    /// Levels of `side`, best first.
+    pub fn levels(&self, side: Side) -> impl DoubleEndedIterator<Item = (&P, &Q)> + ExactSizeIterator {
+        self.side(side).iter().rev().map(|(p, q)| (p, q))
+    }
+
+    /// Adds `qty` at `price`, opening the level if needed.
+    pub fn insert(&mut self, side: Side, price: P, qty: Q)
+    where
+        Q: AddAssign,
+    {
+        match self.find(side, &price) {
+            Ok(i) => self.side_mut(side)[i].1 += qty,
+            Err(i) => self.side_mut(side).insert(i, (price, qty)),
+        }
+    }
+
+    /// Replaces the quantity of an open level, returning the old one.
+    pub fn modify(&mut self, side: Side, price: &P, qty: Q) -> Option<Q> {
+        let i = self.find(side, price).ok()?;
+        Some(std::mem::replace(&mut self.side_mut(side)[i].1, qty))
+    }
+
+    /// Closes the level at `price`, returning its quantity.
+    pub fn cancel(&mut self, side: Side, price: &P) -> Option<Q> {
+        let i = self.find(side, price).ok()?;
+        Some(self.side_mut(side).remove(i).1)
+    }
+
+    /// Depth of the first level not better than `price`, with the `Result` convention:
+    /// for bids the first level priced at or below `price`, for asks at or above it.
+    pub fn lower_bound(&self, side: Side, price: &P) -> Result<usize, usize> {
+        let levels = self.side(side);
+        let better =
+            levels.len() - upper_bound_index(levels.len(), |i| side.cmp(&levels[i].0, price)).unwrap_or_else(|e| e);
+        if better == levels.len() {
+            Err(better)
+        } else {
+            Ok(better)
+        }
+    }
+
+    /// Depth of the first level worse than `price`.
+    pub fn upper_bound(&self, side: Side, price: &P) -> Result<usize, usize> {
+        let levels = self.side(side);
+        let at_least = levels.len() - self.find(side, price).unwrap_or_else(|e| e);
+        if at_least == levels.len() {
+            Err(at_least)
+        } else {
+            Ok(at_least)
+        }
+    }
+
+    /// Total quantity of the levels at `price` or better.
+    pub fn depth_at(&self, side: Side, price: &P) -> Q
+    where
+        Q: for<'a> Sum<&'a Q>,
+    {
+        let levels = self.side(side);
+        levels[self.find(side, price).unwrap_or_else(|e| e)..]
+            .iter()
+            .map(|(_, q)| q)
+            .sum()
+    }
+
+    /// Total quantity of the best `n` levels.
+    pub fn depth(&self, side: Side, n: usize) -> Q
+    where
+        Q: for<'a> Sum<&'a Q>,
+    {
+        self.levels(side).take(n).map(|(_, q)| q).sum()
+    }
+
+    fn side(&self, side: Side) -> &Vec<(P, Q)> {
+        match side {
+            Side::Bid => &self.bids,
+            Side::Ask => &self.asks,
+        }
+    }
+
+    fn side_mut(&mut self, side: Side) -> &mut Vec<(P, Q)> {
+        match side {
+            Side::Bid => &mut self.bids,
+            Side::Ask => &mut self.asks,
+        }
+    }
+
+    /// Position of `price` in the worst-first `Vec` of `side`.
+    fn find(&self, side: Side, price: &P) -> Result<usize, usize> {
+        let levels = self.side(side);
+        match lower_bound_index(levels.len(), |i| side.cmp(&levels[i].0, price)) {
+            Ok(i) if &levels[i].0 == price => Ok(i),
+            pos => Err(pos.unwrap_or_else(|e| e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::{OrderBook, Side};
+
+    #[test]
+    fn test_order_book() {
+        let mut book = OrderBook::new();
+        for (p, q) in [(100, 1), (98, 2), (99, 3), (100, 4), (95, 5)] {
+            book.insert(Side::Bid, p, q);
+        }
+        for (p, q) in [(105, 1), (101, 2), (103, 3)] {
+            book.insert(Side::Ask, p, q);
+        }
+        let bids = book.levels(Side::Bid).map(|(&p, &q)| (p, q)).collect::<Vec<_>>();
+        assert_eq!(bids, [(100, 5), (99, 3), (98, 2), (95, 5)]);
+        let asks = book.levels(Side::Ask).map(|(&p, _)| p).collect::<Vec<_>>();
+        assert_eq!(asks, [101, 103, 105]);
+
+        assert_eq!(book.lower_bound(Side::Bid, &99), Ok(1));
+        assert_eq!(book.upper_bound(Side::Bid, &99), Ok(2));
+        assert_eq!(book.lower_bound(Side::Bid, &97), Ok(3));
+        assert_eq!(book.lower_bound(Side::Bid, &90), Err(4));
+        assert_eq!(book.lower_bound(Side::Ask, &102), Ok(1));
+        assert_eq!(book.upper_bound(Side::Ask, &105), Err(3));
+        assert_eq!(book.lower_bound(Side::Ask, &100), Ok(0));
+
+        assert_eq!(book.depth_at(Side::Bid, &98), 10);
+        assert_eq!(book.depth_at(Side::Bid, &101), 0);
+        assert_eq!(book.depth(Side::Ask, 2), 5);
+        assert_eq!(book.level_at(Side::Bid, 3), Some((&95, &5)));
+        assert_eq!(book.level_at(Side::Bid, 4), None);
+
+        assert_eq!(book.modify(Side::Bid, &99, 7), Some(3));
+        assert_eq!(book.modify(Side::Bid, &97, 7), None);
+        assert_eq!(book.level(Side::Bid, &99), Some(&7));
+        assert_eq!(book.cancel(Side::Bid, &100), Some(5));
+        assert_eq!(book.cancel(Side::Bid, &100), None);
+        assert_eq!(book.best_bid(), Some((&99, &7)));
+        assert_eq!(book.len(Side::Bid), 3);
+    }
+}
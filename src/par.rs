@@ -0,0 +1,178 @@
+use crate::LowerBound;
+use rayon::prelude::*;
+use std::cmp::Ordering;
+
+/// Below this many elements per partition the work is not worth splitting further.
+const MIN_PARTITION: usize = 1 << 14;
+
+/// Parallel set operations on sorted slices, behind the `rayon` feature; assume both slices are sorted.
+///
+/// Both inputs are cut at the same values (found by `lower_bound` on each side), so every
+/// partition can be merged independently and the results simply concatenated.
+/// Duplicates follow the C++ `std::set_*` multiset rules: an element appearing `m` times in
+/// `self` and `n` times in `other` appears `max(m, n)` times in the union, `min(m, n)` times
+/// in the intersection and `m - n` times (if positive) in the difference.
+/// ```
+/// # use bound_stl::ParSetOps;
+/// let a = vec![1, 2, 2, 4, 6];
+/// let b = vec![2, 3, 4, 4];
+/// assert_eq!(a.par_union(&b), vec![1, 2, 2, 3, 4, 4, 6]);
+/// assert_eq!(a.par_intersection(&b), vec![2, 4]);
+/// assert_eq!(a.par_difference(&b), vec![1, 2, 6]);
+/// ```
+pub trait ParSetOps {
+    type Item;
+    fn par_union(&self, other: &[Self::Item]) -> Vec<Self::Item>;
+    fn par_intersection(&self, other: &[Self::Item]) -> Vec<Self::Item>;
+    fn par_difference(&self, other: &[Self::Item]) -> Vec<Self::Item>;
+}
+
+impl<T: Ord + Clone + Send + Sync> ParSetOps for [T] {
+    type Item = T;
+
+    fn par_union(&self, other: &[T]) -> Vec<T> {
+        par_merge(self, other, union)
+    }
+
+    fn par_intersection(&self, other: &[T]) -> Vec<T> {
+        par_merge(self, other, intersection)
+    }
+
+    fn par_difference(&self, other: &[T]) -> Vec<T> {
+        par_merge(self, other, difference)
+    }
+}
+
+fn par_merge<T, F>(a: &[T], b: &[T], merge: F) -> Vec<T>
+where
+    T: Ord + Clone + Send + Sync,
+    F: Fn(&[T], &[T], &mut Vec<T>) + Sync,
+{
+    let cuts = partition(a, b);
+    let parts = cuts
+        .par_windows(2)
+        .map(|w| {
+            let (a, b) = (&a[w[0].0..w[1].0], &b[w[0].1..w[1].1]);
+            let mut out = Vec::with_capacity(a.len().max(b.len()));
+            merge(a, b, &mut out);
+            out
+        })
+        .collect::<Vec<_>>();
+    let mut out = Vec::with_capacity(parts.iter().map(Vec::len).sum());
+    parts.into_iter().for_each(|mut p| out.append(&mut p));
+    out
+}
+
+/// Cut points `(index in a, index in b)`, from `(0, 0)` to `(a.len(), b.len())`.
+/// Pivots are taken from the longer slice; equal elements never straddle a cut.
+fn partition<T: Ord>(a: &[T], b: &[T]) -> Vec<(usize, usize)> {
+    let total = a.len() + b.len();
+    let parts = (rayon::current_num_threads() * 4).min(total / MIN_PARTITION).max(1);
+    let long = if a.len() >= b.len() { a } else { b };
+
+    let mut cuts = vec![(0, 0)];
+    for i in 1..parts {
+        let pivot = &long[i * long.len() / parts];
+        let (x, y) = (lower_bound(a, pivot), lower_bound(b, pivot));
+        if (x, y) != *cuts.last().unwrap() {
+            cuts.push((x, y));
+        }
+    }
+    if (a.len(), b.len()) != *cuts.last().unwrap() {
+        cuts.push((a.len(), b.len()));
+    }
+    cuts
+}
+
+fn lower_bound<T: Ord>(s: &[T], x: &T) -> usize {
+    s.lower_bound(x).unwrap_or_else(|e| e)
+}
+
+fn union<T: Ord + Clone>(a: &[T], b: &[T], out: &mut Vec<T>) {
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            Ordering::Less => {
+                out.push(a[i].clone());
+                i += 1;
+            }
+            Ordering::Greater => {
+                out.push(b[j].clone());
+                j += 1;
+            }
+            Ordering::Equal => {
+                out.push(a[i].clone());
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    out.extend_from_slice(&a[i..]);
+    out.extend_from_slice(&b[j..]);
+}
+
+fn intersection<T: Ord + Clone>(a: &[T], b: &[T], out: &mut Vec<T>) {
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            Ordering::Less => i += 1,
+            Ordering::Greater => j += 1,
+            Ordering::Equal => {
+                out.push(a[i].clone());
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+}
+
+fn difference<T: Ord + Clone>(a: &[T], b: &[T], out: &mut Vec<T>) {
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            Ordering::Less => {
+                out.push(a[i].clone());
+                i += 1;
+            }
+            Ordering::Greater => j += 1,
+            Ordering::Equal => {
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    out.extend_from_slice(&a[i..]);
+}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::ParSetOps;
+    use std::collections::BTreeMap;
+
+    /// The multiset result, built from the count of each value on both sides.
+    fn expected(a: &[u32], b: &[u32], copies: impl Fn(usize, usize) -> usize) -> Vec<u32> {
+        let mut counts = BTreeMap::<u32, (usize, usize)>::new();
+        a.iter().for_each(|&x| counts.entry(x).or_default().0 += 1);
+        b.iter().for_each(|&x| counts.entry(x).or_default().1 += 1);
+        counts
+            .into_iter()
+            .flat_map(|(x, (m, n))| std::iter::repeat_n(x, copies(m, n)))
+            .collect()
+    }
+
+    #[test]
+    fn test_par_set_ops() {
+        // large enough to be split into several partitions, with runs of equal values that
+        // differ in length between the sides
+        let a = (0..200_000u32).map(|x| x / 3 * 2).collect::<Vec<_>>();
+        let b = (0..150_000u32).map(|x| x / 2 * 3).collect::<Vec<_>>();
+
+        assert_eq!(a.par_union(&b), expected(&a, &b, usize::max));
+        assert_eq!(a.par_intersection(&b), expected(&a, &b, usize::min));
+        assert_eq!(a.par_difference(&b), expected(&a, &b, usize::saturating_sub));
+        assert_eq!(b.par_difference(&a), expected(&b, &a, usize::saturating_sub));
+
+        assert!(Vec::<u32>::new().par_intersection(&b).is_empty());
+        assert_eq!(a.par_difference(&[]), a);
+    }
+}
@@ -0,0 +1,171 @@
+use std::cmp::Ordering;
+
+/// Batch updates of a sorted `Vec` in one merge pass.
+///
+/// `deletes` and `inserts` must both be sorted. Each delete removes one element equal to it
+/// from the existing contents, if there is one left, and the inserts are then merged in after
+/// any equal elements, so a patch costs `O(n + m)` instead of `m` separate `O(n)` shifts.
+/// Deletes only see the existing elements, never the inserts of the same patch: to update a
+/// key of a flat map, delete it and insert the new pair. If a comparison or `inserts` panics,
+/// the `Vec` keeps every existing element not yet deleted and stays sorted.
+/// ```
+/// # use bound_stl::ApplyPatch;
+/// let mut v = vec![1, 3, 3, 5, 7];
+/// let deleted = v.apply_patch(vec![2, 3, 8], &[3, 4, 7]);
+/// assert_eq!(deleted, 2);
+/// assert_eq!(v, [1, 2, 3, 3, 5, 8]);
+///
+/// let mut prices = vec![("apple", 3), ("pear", 4), ("plum", 2)];
+/// prices.apply_patch_by_key(vec![("fig", 6), ("pear", 5)], &["pear", "plum"], |p| p.0);
+/// assert_eq!(prices, [("apple", 3), ("fig", 6), ("pear", 5)]);
+/// ```
+pub trait ApplyPatch {
+    type Item;
+
+    /// Returns the number of elements deleted.
+    fn apply_patch<I>(&mut self, inserts: I, deletes: &[Self::Item]) -> usize
+    where
+        I: IntoIterator<Item = Self::Item>;
+
+    /// As [`apply_patch`](Self::apply_patch) for a `Vec` sorted by `f(element)`; a delete removes
+    /// one element with that key.
+    fn apply_patch_by_key<I, K, F>(&mut self, inserts: I, deletes: &[K], f: F) -> usize
+    where
+        I: IntoIterator<Item = Self::Item>,
+        K: Ord,
+        F: FnMut(&Self::Item) -> K;
+}
+
+impl<T: Ord> ApplyPatch for Vec<T> {
+    type Item = T;
+
+    fn apply_patch<I>(&mut self, inserts: I, deletes: &[T]) -> usize
+    where
+        I: IntoIterator<Item = T>,
+    {
+        merge_patch(self, inserts, deletes, |x, other| match other {
+            Other::Delete(d) => x.cmp(d),
+            Other::Element(y) => x.cmp(y),
+        })
+    }
+
+    fn apply_patch_by_key<I, K, F>(&mut self, inserts: I, deletes: &[K], mut f: F) -> usize
+    where
+        I: IntoIterator<Item = T>,
+        K: Ord,
+        F: FnMut(&T) -> K,
+    {
+        merge_patch(self, inserts, deletes, |x, other| match other {
+            Other::Delete(d) => f(x).cmp(d),
+            Other::Element(y) => f(x).cmp(&f(y)),
+        })
+    }
+}
+
+/// What an element is compared against while merging.
+enum Other<'a, T, D> {
+    Delete(&'a D),
+    Element(&'a T),
+}
+
+fn merge_patch<T, D, I, C>(v: &mut Vec<T>, inserts: I, deletes: &[D], mut cmp: C) -> usize
+where
+    I: IntoIterator<Item = T>,
+    C: FnMut(&T, Other<'_, T, D>) -> Ordering,
+{
+    let inserts = inserts.into_iter();
+    let mut merged = Merged {
+        out: Vec::with_capacity(v.len() + inserts.size_hint().0),
+        old: std::mem::take(v).into_iter(),
+        v,
+    };
+    let mut deletes = deletes.iter().peekable();
+    let mut inserts = inserts.peekable();
+    let mut deleted = 0;
+    // `x` leaves `old` only once it is deleted or merged
+    while let Some(x) = merged.old.as_slice().first() {
+        // deletes below `x` match nothing
+        while deletes
+            .next_if(|d| cmp(x, Other::Delete(d)) == Ordering::Greater)
+            .is_some()
+        {}
+        if deletes
+            .next_if(|d| cmp(x, Other::Delete(d)) == Ordering::Equal)
+            .is_some()
+        {
+            merged.old.next();
+            deleted += 1;
+            continue;
+        }
+        while let Some(y) = inserts.next_if(|y| cmp(y, Other::Element(x)) == Ordering::Less) {
+            merged.out.push(y);
+        }
+        merged.out.extend(merged.old.next());
+    }
+    merged.out.extend(inserts);
+    drop(merged);
+    debug_assert!(
+        v.windows(2)
+            .all(|w| cmp(&w[0], Other::Element(&w[1])) != Ordering::Greater),
+        "patch is not sorted"
+    );
+    deleted
+}
+
+/// The merge in progress, written back to `v` when dropped, also on a panic.
+struct Merged<'a, T> {
+    v: &'a mut Vec<T>,
+    out: Vec<T>,
+    old: std::vec::IntoIter<T>,
+}
+
+impl<T> Drop for Merged<'_, T> {
+    fn drop(&mut self) {
+        self.out.extend(&mut self.old);
+        std::mem::swap(self.v, &mut self.out);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::ApplyPatch;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    #[test]
+    fn test_apply_patch() {
+        let mut v = (0..100).map(|x| x / 3 * 2).collect::<Vec<i32>>();
+        let mut expect = v.clone();
+        let inserts = (0..40).map(|x| x * 5 - 3).collect::<Vec<_>>();
+        let deletes = (0..30).map(|x| x * 4).collect::<Vec<_>>();
+        let mut deleted = 0;
+        for d in &deletes {
+            if let Some(i) = expect.iter().position(|x| x == d) {
+                expect.remove(i);
+                deleted += 1;
+            }
+        }
+        for x in &inserts {
+            let i = expect.partition_point(|y| y <= x);
+            expect.insert(i, *x);
+        }
+        assert_eq!(v.apply_patch(inserts, &deletes), deleted);
+        assert_eq!(v, expect);
+
+        let mut empty = Vec::new();
+        assert_eq!(empty.apply_patch([1, 2], &[1]), 0);
+        assert_eq!(empty, [1, 2]);
+
+        // inserts with equal keys land after the existing ones
+        let mut pairs = vec![(1, 'a'), (2, 'b')];
+        assert_eq!(pairs.apply_patch_by_key([(1, 'z'), (3, 'c')], &[2, 2], |p| p.0), 1);
+        assert_eq!(pairs, [(1, 'a'), (1, 'z'), (3, 'c')]);
+
+        // a panicking key function leaves the elements not yet deleted in place
+        let mut v = vec![1, 2, 3, 4, 5];
+        let res = catch_unwind(AssertUnwindSafe(|| {
+            v.apply_patch_by_key([0], &[2], |&x| if x == 4 { panic!("bad key") } else { x })
+        }));
+        assert!(res.is_err());
+        assert_eq!(v, [0, 1, 3, 4, 5]);
+    }
+}
@@ -0,0 +1,83 @@
+use crate::{LowerBound, UpperBound};
+use std::cmp::Ordering;
+
+/// `lower_bound` with the C++ convention: the position is returned as is, and `len`
+/// stands for `end()` where [`LowerBound`] returns `Err(len)`.
+/// It is implemented for every [`LowerBound`] type.
+/// ```
+/// # use bound_stl::LowerBoundPos;
+/// let v = vec![1, 2, 4];
+/// assert_eq!(v.lower_bound_pos(&2), 1);
+/// assert_eq!(v.lower_bound_pos(&5), v.len());
+/// ```
+pub trait LowerBoundPos: LowerBound {
+    fn lower_bound_pos(&self, x: &Self::Item) -> usize {
+        self.lower_bound(x).unwrap_or_else(|e| e)
+    }
+
+    fn lower_bound_pos_by<'a, F>(&'a self, f: F) -> usize
+    where
+        F: FnMut(&'a Self::Item) -> Ordering,
+    {
+        self.lower_bound_by(f).unwrap_or_else(|e| e)
+    }
+
+    fn lower_bound_pos_by_key<'a, K, F>(&'a self, k: &K, f: F) -> usize
+    where
+        F: FnMut(&'a Self::Item) -> K,
+        K: Ord,
+    {
+        self.lower_bound_by_key(k, f).unwrap_or_else(|e| e)
+    }
+}
+
+impl<S: LowerBound + ?Sized> LowerBoundPos for S {}
+
+/// `upper_bound` with the C++ convention, see [`LowerBoundPos`].
+/// ```
+/// # use bound_stl::UpperBoundPos;
+/// let v = vec![1, 2, 4];
+/// assert_eq!(v.upper_bound_pos(&2), 2);
+/// assert_eq!(v.upper_bound_pos(&4), v.len());
+/// ```
+pub trait UpperBoundPos: UpperBound {
+    fn upper_bound_pos(&self, x: &Self::Item) -> usize {
+        self.upper_bound(x).unwrap_or_else(|e| e)
+    }
+
+    fn upper_bound_pos_by<'a, F>(&'a self, f: F) -> usize
+    where
+        F: FnMut(&'a Self::Item) -> Ordering,
+    {
+        self.upper_bound_by(f).unwrap_or_else(|e| e)
+    }
+
+    fn upper_bound_pos_by_key<'a, K, F>(&'a self, k: &K, f: F) -> usize
+    where
+        F: FnMut(&'a Self::Item) -> K,
+        K: Ord,
+    {
+        self.upper_bound_by_key(k, f).unwrap_or_else(|e| e)
+    }
+}
+
+impl<S: UpperBound + ?Sized> UpperBoundPos for S {}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::{LowerBoundPos, UpperBoundPos};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_bound_pos() {
+        let v = vec![1, 3, 3, 5];
+        let ends = [0, 2, 3, 4, 6].map(|x| (v.lower_bound_pos(&x), v.upper_bound_pos(&x)));
+        assert_eq!(ends, [(0, 0), (1, 1), (1, 3), (3, 3), (4, 4)]);
+        assert_eq!(v[..0].lower_bound_pos(&1), 0);
+        assert_eq!(v.lower_bound_pos_by(|y| y.cmp(&4)), 3);
+        assert_eq!(v.upper_bound_pos_by_key(&1, |y| y / 2), 3);
+
+        let m = BTreeMap::from([(1, 'a'), (2, 'b')]);
+        assert_eq!(m.upper_bound_pos(&2), 2);
+    }
+}
@@ -0,0 +1,217 @@
+use crate::{LowerBound, UpperBound};
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// A Bloom filter sized by bits per expected element.
+#[derive(Debug, Clone)]
+struct Bloom {
+    bits: Vec<u64>,
+    hashes: u32,
+}
+
+impl Bloom {
+    fn new(capacity: usize, bits_per_item: usize) -> Self {
+        let m = (capacity.max(1) * bits_per_item).div_ceil(64).max(1);
+        // k = ln 2 * m / n is the false-positive optimum
+        let hashes = ((bits_per_item as f64) * std::f64::consts::LN_2)
+            .round()
+            .clamp(1.0, 16.0) as u32;
+        Bloom {
+            bits: vec![0; m],
+            hashes,
+        }
+    }
+
+    fn positions<T: Hash>(&self, x: &T) -> impl Iterator<Item = usize> {
+        let mut h = DefaultHasher::new();
+        x.hash(&mut h);
+        let h = h.finish();
+        let (h1, h2) = (h as u32 as u64, (h >> 32) | 1);
+        let m = self.bits.len() as u64 * 64;
+        (0..self.hashes as u64).map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % m) as usize)
+    }
+
+    fn insert<T: Hash>(&mut self, x: &T) {
+        for p in self.positions(x).collect::<Vec<_>>() {
+            self.bits[p / 64] |= 1 << (p % 64);
+        }
+    }
+
+    fn may_contain<T: Hash>(&self, x: &T) -> bool {
+        self.positions(x).all(|p| self.bits[p / 64] >> (p % 64) & 1 == 1)
+    }
+}
+
+/// A sorted `Vec` with an opt-in Bloom filter in front of its exact-match lookups.
+///
+/// [`contains`](Self::contains) and [`find`](Self::find) first ask the filter, and only search
+/// when it says the element may be present, so lookups for absent elements mostly skip the
+/// binary search. Bound queries are answered by the sorted data as usual. The filter does not
+/// forget removed elements: it grows more permissive until [`rebuild_filter`](Self::rebuild_filter),
+/// and it is rebuilt automatically when the data outgrows it.
+/// ```
+/// # use bound_stl::{LowerBound, PreFiltered};
+/// let mut ids = PreFiltered::new(vec![40, 10, 30, 20]);
+/// assert!(ids.contains(&30));
+/// assert!(!ids.contains(&35));
+/// ids.insert(35);
+/// assert_eq!(ids.find(&35), Some(3));
+/// assert_eq!(ids.lower_bound(&36), Ok(4));
+/// ```
+#[derive(Debug, Clone)]
+pub struct PreFiltered<T> {
+    items: Vec<T>,
+    filter: Bloom,
+    capacity: usize,
+    bits_per_item: usize,
+}
+
+impl<T: Ord + Hash> PreFiltered<T> {
+    /// Sorts `items` and builds a filter with 10 bits per element, about 1% false positives.
+    pub fn new(items: Vec<T>) -> Self {
+        Self::with_bits_per_item(items, 10)
+    }
+
+    /// Sorts `items` and builds a filter with the given number of bits per element;
+    /// more bits give fewer false positives.
+    pub fn with_bits_per_item(mut items: Vec<T>, bits_per_item: usize) -> Self {
+        assert!(bits_per_item > 0, "bits per item must not be zero");
+        items.sort();
+        let mut this = PreFiltered {
+            items,
+            filter: Bloom::new(0, bits_per_item),
+            capacity: 0,
+            bits_per_item,
+        };
+        this.rebuild_filter();
+        this
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.items
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.items
+    }
+
+    pub fn contains(&self, x: &T) -> bool {
+        self.find(x).is_some()
+    }
+
+    /// What the filter alone says: `false` means `x` is certainly absent.
+    pub fn may_contain(&self, x: &T) -> bool {
+        self.filter.may_contain(x)
+    }
+
+    /// Index of the first element equal to `x`.
+    pub fn find(&self, x: &T) -> Option<usize> {
+        if !self.filter.may_contain(x) {
+            return None;
+        }
+        self.items.lower_bound(x).ok().filter(|&i| self.items[i] == *x)
+    }
+
+    /// Inserts `x` after any equal elements and returns its index.
+    pub fn insert(&mut self, x: T) -> usize {
+        let pos = self.items.upper_bound(&x).unwrap_or_else(|e| e);
+        self.filter.insert(&x);
+        self.items.insert(pos, x);
+        if self.items.len() > self.capacity {
+            self.rebuild_filter();
+        }
+        pos
+    }
+
+    /// Removes one element equal to `x`, if any.
+    pub fn remove(&mut self, x: &T) -> Option<T> {
+        self.find(x).map(|i| self.items.remove(i))
+    }
+
+    /// Rebuilds the filter from the current elements, dropping what removed elements left behind.
+    pub fn rebuild_filter(&mut self) {
+        self.capacity = (self.items.len() * 2).max(16);
+        self.filter = Bloom::new(self.capacity, self.bits_per_item);
+        for x in &self.items {
+            self.filter.insert(x);
+        }
+    }
+}
+
+impl<T: Ord> LowerBound for PreFiltered<T> {
+    type Item = T;
+    fn lower_bound(&self, x: &Self::Item) -> Result<usize, usize> {
+        self.items.lower_bound(x)
+    }
+
+    fn lower_bound_by<'a, F>(&'a self, f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> Ordering,
+    {
+        self.items.lower_bound_by(f)
+    }
+
+    fn lower_bound_by_key<'a, K, F>(&'a self, k: &K, f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> K,
+        K: Ord,
+    {
+        self.items.lower_bound_by_key(k, f)
+    }
+}
+
+impl<T: Ord> UpperBound for PreFiltered<T> {
+    type Item = T;
+    fn upper_bound(&self, x: &Self::Item) -> Result<usize, usize> {
+        self.items.upper_bound(x)
+    }
+
+    fn upper_bound_by<'a, F>(&'a self, f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> Ordering,
+    {
+        self.items.upper_bound_by(f)
+    }
+
+    fn upper_bound_by_key<'a, K, F>(&'a self, k: &K, f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> K,
+        K: Ord,
+    {
+        self.items.upper_bound_by_key(k, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::PreFiltered;
+
+    #[test]
+    fn test_pre_filtered() {
+        let mut set = PreFiltered::new((0..1000u32).map(|x| x * 2).collect());
+        assert!((0..1000).all(|x| set.contains(&(x * 2))));
+        let false_positives = (0..1000).filter(|x| set.may_contain(&(x * 2 + 1))).count();
+        assert!(false_positives < 50, "{false_positives} false positives");
+        assert!((0..1000).all(|x| !set.contains(&(x * 2 + 1))));
+
+        for x in 0..5000u32 {
+            set.insert(x * 2 + 1);
+        }
+        assert_eq!(set.len(), 6000);
+        assert!(set.as_slice().windows(2).all(|w| w[0] <= w[1]));
+        assert!(set.contains(&9999) && set.contains(&1998));
+        assert_eq!(set.remove(&1998), Some(1998));
+        assert!(!set.contains(&1998));
+        set.rebuild_filter();
+        assert!(set.contains(&1996));
+    }
+}
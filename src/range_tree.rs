@@ -0,0 +1,208 @@
+use crate::range_index;
+use std::ops::RangeBounds;
+
+#[derive(Debug, Clone)]
+struct Node<Y> {
+    /// x-sorted positions covered by this node
+    lo: usize,
+    hi: usize,
+    /// y values of the covered points, sorted, with the matching point indices
+    ys: Vec<Y>,
+    ids: Vec<usize>,
+    /// `left[i]`: how many of the first `i` entries of `ys` come from the left child,
+    /// which is where a position in `ys` lands in the left child (fractional cascading)
+    left: Vec<usize>,
+    children: Option<(usize, usize)>,
+}
+
+/// A static 2-D range tree answering orthogonal range counting and reporting queries:
+/// points with `x` in one range and `y` in another.
+///
+/// The points are split by `x` into a balanced tree whose nodes keep their points sorted by
+/// `y`. A query does one `y` bound search at the root; fractional cascading carries the
+/// result down to the children, so counting costs `O(log n)` and reporting `O(log n + k)`,
+/// in `O(n log n)` space. Reported indices refer to the slice the tree was built from.
+/// ```
+/// # use bound_stl::RangeTree2D;
+/// let points = [(1, 5), (2, 2), (3, 8), (4, 4), (6, 1), (7, 7)];
+/// let tree = RangeTree2D::new(&points);
+/// assert_eq!(tree.count(2..=6, 2..=5), 2);          // (2, 2) and (4, 4)
+/// assert_eq!(tree.report_indices(.., 6..), vec![2, 5]); // (3, 8) and (7, 7)
+/// ```
+#[derive(Debug, Clone)]
+pub struct RangeTree2D<X, Y> {
+    points: Vec<(X, Y)>,
+    /// point indices sorted by x
+    by_x: Vec<usize>,
+    nodes: Vec<Node<Y>>,
+}
+
+impl<X: Ord + Clone, Y: Ord + Clone> RangeTree2D<X, Y> {
+    pub fn new(points: &[(X, Y)]) -> Self {
+        let mut by_x = (0..points.len()).collect::<Vec<_>>();
+        by_x.sort_by(|&a, &b| points[a].0.cmp(&points[b].0));
+        let mut tree = RangeTree2D {
+            points: points.to_vec(),
+            by_x,
+            nodes: Vec::new(),
+        };
+        if !points.is_empty() {
+            tree.build(0, points.len());
+        }
+        tree
+    }
+
+    /// Builds the node for x-sorted positions `lo..hi` and returns its index.
+    fn build(&mut self, lo: usize, hi: usize) -> usize {
+        if hi - lo == 1 {
+            let id = self.by_x[lo];
+            self.nodes.push(Node {
+                lo,
+                hi,
+                ys: vec![self.points[id].1.clone()],
+                ids: vec![id],
+                left: vec![0, 0],
+                children: None,
+            });
+            return self.nodes.len() - 1;
+        }
+        let mid = lo + (hi - lo) / 2;
+        let (l, r) = (self.build(lo, mid), self.build(mid, hi));
+        let (a, b) = (&self.nodes[l], &self.nodes[r]);
+        let len = a.ys.len() + b.ys.len();
+        let (mut ys, mut ids, mut left) = (
+            Vec::with_capacity(len),
+            Vec::with_capacity(len),
+            Vec::with_capacity(len + 1),
+        );
+        let (mut i, mut j) = (0, 0);
+        left.push(0);
+        while i < a.ys.len() || j < b.ys.len() {
+            // ties go left first, so `left` is right for both lower and upper bounds
+            if j == b.ys.len() || (i < a.ys.len() && a.ys[i] <= b.ys[j]) {
+                ys.push(a.ys[i].clone());
+                ids.push(a.ids[i]);
+                i += 1;
+            } else {
+                ys.push(b.ys[j].clone());
+                ids.push(b.ids[j]);
+                j += 1;
+            }
+            left.push(i);
+        }
+        self.nodes.push(Node {
+            lo,
+            hi,
+            ys,
+            ids,
+            left,
+            children: Some((l, r)),
+        });
+        self.nodes.len() - 1
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    pub fn points(&self) -> &[(X, Y)] {
+        &self.points
+    }
+
+    /// Number of points with `x` in `xr` and `y` in `yr`.
+    pub fn count<XR: RangeBounds<X>, YR: RangeBounds<Y>>(&self, xr: XR, yr: YR) -> usize {
+        let mut count = 0;
+        self.visit(xr, yr, |_, yl, yh| count += yh - yl);
+        count
+    }
+
+    /// Indices of the points with `x` in `xr` and `y` in `yr`, in increasing order.
+    pub fn report_indices<XR: RangeBounds<X>, YR: RangeBounds<Y>>(&self, xr: XR, yr: YR) -> Vec<usize> {
+        let mut out = Vec::new();
+        self.visit(xr, yr, |node, yl, yh| out.extend_from_slice(&node.ids[yl..yh]));
+        out.sort_unstable();
+        out
+    }
+
+    /// The points with `x` in `xr` and `y` in `yr`, in the order they were given.
+    pub fn report<XR: RangeBounds<X>, YR: RangeBounds<Y>>(&self, xr: XR, yr: YR) -> Vec<&(X, Y)> {
+        self.report_indices(xr, yr)
+            .into_iter()
+            .map(|i| &self.points[i])
+            .collect()
+    }
+
+    /// Calls `f(node, yl, yh)` for every canonical node inside the x range, with `yl..yh` the
+    /// matching slice of its y list.
+    fn visit<'a, XR, YR, F>(&'a self, xr: XR, yr: YR, mut f: F)
+    where
+        XR: RangeBounds<X>,
+        YR: RangeBounds<Y>,
+        F: FnMut(&'a Node<Y>, usize, usize),
+    {
+        let Some(root) = self.nodes.last() else {
+            return;
+        };
+        let xs = range_index(self.by_x.len(), xr, |p, x| self.points[self.by_x[p]].0.cmp(x));
+        let ys = range_index(root.ys.len(), yr, |i, y| root.ys[i].cmp(y));
+        if xs.is_empty() || ys.is_empty() {
+            return;
+        }
+        let (xl, xh) = (xs.start, xs.end);
+        let mut stack = vec![(self.nodes.len() - 1, ys.start, ys.end)];
+        while let Some((n, yl, yh)) = stack.pop() {
+            let node = &self.nodes[n];
+            if yl == yh || node.hi <= xl || xh <= node.lo {
+                continue;
+            }
+            if xl <= node.lo && node.hi <= xh {
+                f(node, yl, yh);
+            } else if let Some((l, r)) = node.children {
+                let (ll, lh) = (node.left[yl], node.left[yh]);
+                stack.push((l, ll, lh));
+                stack.push((r, yl - ll, yh - lh));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::RangeTree2D;
+
+    #[test]
+    fn test_range_tree_2d() {
+        let mut seed = 99u64;
+        let mut rand = || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 33) as i32 % 20
+        };
+        let points = (0..150).map(|_| (rand(), rand())).collect::<Vec<_>>();
+        let tree = RangeTree2D::new(&points);
+        for (a, b, c, d) in [
+            (0, 19, 0, 19),
+            (3, 7, 5, 15),
+            (5, 5, 0, 19),
+            (10, 2, 0, 19),
+            (-5, 4, 18, 30),
+        ] {
+            let expected = (0..points.len())
+                .filter(|&i| (a..=b).contains(&points[i].0) && (c..=d).contains(&points[i].1))
+                .collect::<Vec<_>>();
+            assert_eq!(tree.count(a..=b, c..=d), expected.len());
+            assert_eq!(tree.report_indices(a..=b, c..=d), expected);
+        }
+        assert_eq!(tree.count(.., ..), 150);
+        assert_eq!(tree.count(3..3, ..), 0);
+        assert_eq!(tree.report(.., 19..).len(), points.iter().filter(|p| p.1 >= 19).count());
+
+        let empty = RangeTree2D::<i32, i32>::new(&[]);
+        assert_eq!(empty.count(.., ..), 0);
+        let one = RangeTree2D::new(&[("a", 1.5f32.to_bits())]);
+        assert_eq!(one.count("a"..="a", ..), 1);
+    }
+}
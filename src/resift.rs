@@ -0,0 +1,92 @@
+use std::cmp::Ordering;
+
+/// Restores the order of a sorted slice after one element was modified in place.
+///
+/// `resift(i)` rotates element `i` to where it now belongs and returns its new index.
+/// Elements equal to it are not passed over, so it moves as little as possible, and the
+/// cost is a binary search plus the rotation, with nothing beyond the moved span touched.
+/// ```
+/// # use bound_stl::Resift;
+/// let mut v = vec![10, 20, 30, 40, 50];
+/// v[1] = 45;
+/// assert_eq!(v.resift(1), 3);
+/// assert_eq!(v, [10, 30, 40, 45, 50]);
+///
+/// let mut users = vec![("ann", 3), ("bob", 5), ("cid", 8)];
+/// users[2].1 = 1;
+/// assert_eq!(users.resift_by_key(2, |u| u.1), 0);
+/// assert_eq!(users, [("cid", 1), ("ann", 3), ("bob", 5)]);
+/// ```
+pub trait Resift {
+    type Item;
+
+    fn resift(&mut self, i: usize) -> usize;
+
+    fn resift_by<F>(&mut self, i: usize, f: F) -> usize
+    where
+        F: FnMut(&Self::Item, &Self::Item) -> Ordering;
+
+    fn resift_by_key<K, F>(&mut self, i: usize, f: F) -> usize
+    where
+        F: FnMut(&Self::Item) -> K,
+        K: Ord;
+}
+
+impl<T: Ord> Resift for [T] {
+    type Item = T;
+
+    fn resift(&mut self, i: usize) -> usize {
+        self.resift_by(i, |a, b| a.cmp(b))
+    }
+
+    fn resift_by<F>(&mut self, i: usize, mut f: F) -> usize
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let (left, rest) = self.split_at(i);
+        let (x, right) = rest.split_first().expect("index out of bounds");
+        if left.last().is_some_and(|y| f(y, x) == Ordering::Greater) {
+            // everything before the new position is not greater than `x`
+            let p = left.partition_point(|y| f(y, x) != Ordering::Greater);
+            self[p..=i].rotate_right(1);
+            p
+        } else if right.first().is_some_and(|y| f(y, x) == Ordering::Less) {
+            let q = right.partition_point(|y| f(y, x) == Ordering::Less);
+            self[i..=i + q].rotate_left(1);
+            i + q
+        } else {
+            i
+        }
+    }
+
+    fn resift_by_key<K, F>(&mut self, i: usize, mut f: F) -> usize
+    where
+        F: FnMut(&T) -> K,
+        K: Ord,
+    {
+        self.resift_by(i, |a, b| f(a).cmp(&f(b)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::Resift;
+
+    #[test]
+    fn test_resift() {
+        let base = [1, 3, 3, 5, 7, 7, 9];
+        for i in 0..base.len() {
+            for x in 0..11 {
+                let mut v = base;
+                v[i] = x;
+                let j = v.resift(i);
+                assert!(v.is_sorted(), "i = {i}, x = {x}");
+                assert_eq!(v[j], x);
+                // no element equal to `x` was passed over
+                let (lo, hi) = (i.min(j), i.max(j));
+                assert!(base[lo..=hi].iter().enumerate().all(|(k, &y)| k + lo == i || y != x));
+            }
+        }
+        assert_eq!([4].resift(0), 0);
+    }
+}
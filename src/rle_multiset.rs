@@ -0,0 +1,240 @@
+use crate::{lower_bound_index, upper_bound_index, LowerBound, UpperBound};
+use std::cmp::Ordering;
+
+/// A sorted multiset stored as `(value, count)` runs.
+///
+/// Positions, ranks and bounds refer to the expanded sequence, as if every copy were stored:
+/// for `{1 × 2, 5 × 3}` the logical sequence is `[1, 1, 5, 5, 5]`. Running totals are kept per
+/// run, so `rank`, `select` and the bound queries are binary searches over the runs.
+/// ```
+/// # use bound_stl::{LowerBound, RleMultiSet, UpperBound};
+/// let mut s = RleMultiSet::new();
+/// s.insert_n(5, 3);
+/// s.insert_n(1, 2);
+/// assert_eq!(s.len(), 5);
+/// assert_eq!(s.count(&5), 3);
+/// assert_eq!(s.rank(&5), 2);
+/// assert_eq!(s.select(4), Some(&5));
+/// assert_eq!(s.lower_bound(&3), Ok(2));
+/// assert_eq!(s.upper_bound(&5), Err(5));
+/// assert_eq!(s.remove_n(&5, 10), 3);
+/// assert_eq!(s.iter().collect::<Vec<_>>(), vec![&1, &1]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RleMultiSet<T> {
+    runs: Vec<(T, usize)>,
+    /// `ends[i]`: number of elements in runs `0..=i`
+    ends: Vec<usize>,
+}
+
+impl<T> Default for RleMultiSet<T> {
+    fn default() -> Self {
+        RleMultiSet {
+            runs: Vec::new(),
+            ends: Vec::new(),
+        }
+    }
+}
+
+impl<T: Ord> RleMultiSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of elements, counting every copy.
+    pub fn len(&self) -> usize {
+        self.ends.last().copied().unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.runs.is_empty()
+    }
+
+    /// Number of distinct values.
+    pub fn run_count(&self) -> usize {
+        self.runs.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.runs.clear();
+        self.ends.clear();
+    }
+
+    pub fn insert(&mut self, value: T) {
+        self.insert_n(value, 1);
+    }
+
+    /// Adds `n` copies of `value`.
+    pub fn insert_n(&mut self, value: T, n: usize) {
+        if n == 0 {
+            return;
+        }
+        let i = match self.find_run(&value) {
+            Ok(i) => {
+                self.runs[i].1 += n;
+                i
+            }
+            Err(i) => {
+                self.runs.insert(i, (value, n));
+                self.ends.insert(i, self.start_of(i));
+                i
+            }
+        };
+        self.ends[i..].iter_mut().for_each(|e| *e += n);
+    }
+
+    /// Removes up to `n` copies of `value` and returns how many were removed.
+    pub fn remove_n(&mut self, value: &T, n: usize) -> usize {
+        let Ok(i) = self.find_run(value) else {
+            return 0;
+        };
+        let removed = n.min(self.runs[i].1);
+        self.runs[i].1 -= removed;
+        self.ends[i..].iter_mut().for_each(|e| *e -= removed);
+        if self.runs[i].1 == 0 {
+            self.runs.remove(i);
+            self.ends.remove(i);
+        }
+        removed
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        self.find_run(value).is_ok()
+    }
+
+    pub fn count(&self, value: &T) -> usize {
+        self.find_run(value).map_or(0, |i| self.runs[i].1)
+    }
+
+    /// Number of elements less than `value`.
+    pub fn rank(&self, value: &T) -> usize {
+        self.start_of(self.find_run(value).unwrap_or_else(|e| e))
+    }
+
+    /// The element at position `idx` of the expanded sequence.
+    pub fn select(&self, idx: usize) -> Option<&T> {
+        let run = self.ends.partition_point(|&e| e <= idx);
+        self.runs.get(run).map(|(v, _)| v)
+    }
+
+    /// Every element, repeated by its count.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.runs.iter().flat_map(|(v, n)| std::iter::repeat_n(v, *n))
+    }
+
+    /// The distinct values with their counts.
+    pub fn runs(&self) -> &[(T, usize)] {
+        &self.runs
+    }
+
+    fn find_run(&self, value: &T) -> Result<usize, usize> {
+        self.runs.binary_search_by(|(v, _)| v.cmp(value))
+    }
+
+    /// Expanded position of the first element of run `i`.
+    fn start_of(&self, i: usize) -> usize {
+        if i == 0 {
+            0
+        } else {
+            self.ends[i - 1]
+        }
+    }
+
+    /// Maps a run-level bound to an expanded position with the `Result` convention.
+    fn expand(&self, run: Result<usize, usize>) -> Result<usize, usize> {
+        let pos = self.start_of(run.unwrap_or_else(|e| e));
+        if pos == self.len() {
+            Err(pos)
+        } else {
+            Ok(pos)
+        }
+    }
+}
+
+impl<T: Ord> FromIterator<T> for RleMultiSet<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut s = RleMultiSet::new();
+        iter.into_iter().for_each(|v| s.insert(v));
+        s
+    }
+}
+
+impl<T: Ord> LowerBound for RleMultiSet<T> {
+    type Item = T;
+    fn lower_bound(&self, x: &Self::Item) -> Result<usize, usize> {
+        self.lower_bound_by(|y| y.cmp(x))
+    }
+
+    fn lower_bound_by<'a, F>(&'a self, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> Ordering,
+    {
+        self.expand(lower_bound_index(self.runs.len(), |i| f(&self.runs[i].0)))
+    }
+
+    fn lower_bound_by_key<'a, K, F>(&'a self, k: &K, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> K,
+        K: Ord,
+    {
+        self.lower_bound_by(|y| f(y).cmp(k))
+    }
+}
+
+impl<T: Ord> UpperBound for RleMultiSet<T> {
+    type Item = T;
+    fn upper_bound(&self, x: &Self::Item) -> Result<usize, usize> {
+        self.upper_bound_by(|y| y.cmp(x))
+    }
+
+    fn upper_bound_by<'a, F>(&'a self, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> Ordering,
+    {
+        self.expand(upper_bound_index(self.runs.len(), |i| f(&self.runs[i].0)))
+    }
+
+    fn upper_bound_by_key<'a, K, F>(&'a self, k: &K, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> K,
+        K: Ord,
+    {
+        self.upper_bound_by(|y| f(y).cmp(k))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::{LowerBound, RleMultiSet, UpperBound};
+
+    #[test]
+    fn test_rle_multiset() {
+        let data = [4, 1, 4, 9, 4, 1, 7, 7, 7, 7];
+        let s = data.iter().copied().collect::<RleMultiSet<_>>();
+        let mut expanded = data.to_vec();
+        expanded.sort();
+        assert_eq!(s.run_count(), 4);
+        assert!(s.iter().eq(expanded.iter()));
+        for x in 0..11 {
+            assert_eq!(s.lower_bound(&x), expanded.lower_bound(&x));
+            assert_eq!(s.upper_bound(&x), expanded.upper_bound(&x));
+            assert_eq!(s.rank(&x), expanded.iter().filter(|&&e| e < x).count());
+            assert_eq!(s.count(&x), expanded.iter().filter(|&&e| e == x).count());
+        }
+        for (i, e) in expanded.iter().enumerate() {
+            assert_eq!(s.select(i), Some(e));
+        }
+        assert_eq!(s.select(expanded.len()), None);
+
+        let mut s = s;
+        assert_eq!(s.remove_n(&4, 2), 2);
+        assert_eq!(s.remove_n(&5, 2), 0);
+        assert_eq!(s.remove_n(&1, 5), 2);
+        assert!(!s.contains(&1));
+        assert_eq!(s.runs(), &[(4, 1), (7, 4), (9, 1)]);
+        assert_eq!(s.len(), 6);
+        assert_eq!(s.select(5), Some(&9));
+        s.insert_n(0, 0);
+        assert_eq!(s.run_count(), 3);
+    }
+}
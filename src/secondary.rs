@@ -0,0 +1,194 @@
+use crate::{range_index, LowerBound, UpperBound};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::ops::RangeBounds;
+
+/// A value-ordered view over the entries of a keyed store.
+///
+/// The index holds `(value, key)` pairs sorted by value, where the value is whatever the entry
+/// should be ordered by: the stored value itself or a key derived from it. It does not own the
+/// store; keep it in sync by calling [`insert`](Self::insert) and [`remove`](Self::remove)
+/// whenever the store changes. Positions returned by `lower_bound` / `upper_bound` are ranks in
+/// value order, entries with equal values are ordered by key.
+/// ```
+/// # use bound_stl::{LowerBound, SecondaryIndex};
+/// # use std::collections::HashMap;
+/// let scores = HashMap::from([("ann", 70), ("bob", 85), ("cid", 85), ("dan", 40)]);
+/// let mut by_score = SecondaryIndex::from_entries(scores.iter().map(|(&k, &v)| (k, v)));
+///
+/// let at_least_80 = by_score.range(80..).map(|(_, &k)| k).collect::<Vec<_>>();
+/// assert_eq!(at_least_80, vec!["bob", "cid"]);
+///
+/// by_score.insert("dan", 90); // dan's score changed in the store
+/// assert_eq!(by_score.lower_bound(&80), Ok(1));
+/// assert_eq!(by_score.iter().last(), Some((&90, &"dan")));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SecondaryIndex<K, V> {
+    entries: Vec<(V, K)>,
+    values: BTreeMap<K, V>,
+}
+
+impl<K: Ord + Clone, V: Ord + Clone> Default for SecondaryIndex<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord + Clone, V: Ord + Clone> SecondaryIndex<K, V> {
+    pub fn new() -> Self {
+        SecondaryIndex {
+            entries: Vec::new(),
+            values: BTreeMap::new(),
+        }
+    }
+
+    /// Builds the index in one sort; for a repeated key the last value wins.
+    pub fn from_entries<I: IntoIterator<Item = (K, V)>>(entries: I) -> Self {
+        let values = entries.into_iter().collect::<BTreeMap<_, _>>();
+        let mut entries = values.iter().map(|(k, v)| (v.clone(), k.clone())).collect::<Vec<_>>();
+        entries.sort_unstable();
+        SecondaryIndex { entries, values }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.values.clear();
+    }
+
+    /// The indexed value of `key`.
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.values.get(key)
+    }
+
+    /// Indexes `key` under `value`, replacing and returning its previous value.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let old = self.remove(&key);
+        let pos = self.position(&value, &key).unwrap_or_else(|e| e);
+        self.entries.insert(pos, (value.clone(), key.clone()));
+        self.values.insert(key, value);
+        old
+    }
+
+    /// Drops `key` from the index, returning its value.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let value = self.values.remove(key)?;
+        let pos = self.position(&value, key).expect("secondary index out of sync");
+        self.entries.remove(pos);
+        Some(value)
+    }
+
+    /// The entry at rank `idx` in value order.
+    pub fn get_index(&self, idx: usize) -> Option<(&V, &K)> {
+        self.entries.get(idx).map(|(v, k)| (v, k))
+    }
+
+    /// Entries in value order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&V, &K)> + ExactSizeIterator {
+        self.entries.iter().map(|(v, k)| (v, k))
+    }
+
+    /// Entries whose value lies in `range`, in value order.
+    pub fn range<R: RangeBounds<V>>(&self, range: R) -> impl DoubleEndedIterator<Item = (&V, &K)> {
+        let span = range_index(self.entries.len(), range, |i, v| self.entries[i].0.cmp(v));
+        self.entries[span].iter().map(|(v, k)| (v, k))
+    }
+
+    /// Drops the first `end` entries in value order and returns them.
+    pub(crate) fn drain_front(&mut self, end: usize) -> Vec<(V, K)> {
+        let drained = self.entries.drain(..end).collect::<Vec<_>>();
+        for (_, k) in &drained {
+            self.values.remove(k);
+        }
+        drained
+    }
+
+    fn position(&self, value: &V, key: &K) -> Result<usize, usize> {
+        self.entries
+            .binary_search_by(|(v, k)| v.cmp(value).then_with(|| k.cmp(key)))
+    }
+}
+
+impl<K: Ord, V: Ord> LowerBound for SecondaryIndex<K, V> {
+    type Item = V;
+    fn lower_bound(&self, x: &Self::Item) -> Result<usize, usize> {
+        self.lower_bound_by(|y| y.cmp(x))
+    }
+
+    fn lower_bound_by<'a, F>(&'a self, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> Ordering,
+    {
+        self.entries.lower_bound_by(|(v, _)| f(v))
+    }
+
+    fn lower_bound_by_key<'a, Q, F>(&'a self, k: &Q, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> Q,
+        Q: Ord,
+    {
+        self.lower_bound_by(|y| f(y).cmp(k))
+    }
+}
+
+impl<K: Ord, V: Ord> UpperBound for SecondaryIndex<K, V> {
+    type Item = V;
+    fn upper_bound(&self, x: &Self::Item) -> Result<usize, usize> {
+        self.upper_bound_by(|y| y.cmp(x))
+    }
+
+    fn upper_bound_by<'a, F>(&'a self, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> Ordering,
+    {
+        self.entries.upper_bound_by(|(v, _)| f(v))
+    }
+
+    fn upper_bound_by_key<'a, Q, F>(&'a self, k: &Q, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> Q,
+        Q: Ord,
+    {
+        self.upper_bound_by(|y| f(y).cmp(k))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::{LowerBound, SecondaryIndex, UpperBound};
+
+    #[test]
+    fn test_secondary_index() {
+        let mut idx = SecondaryIndex::new();
+        assert_eq!(idx.lower_bound(&0), Err(0));
+        for (k, v) in [(1, 30), (2, 10), (3, 20), (4, 10)] {
+            assert_eq!(idx.insert(k, v), None);
+        }
+        assert_eq!(idx.iter().map(|(_, &k)| k).collect::<Vec<_>>(), vec![2, 4, 3, 1]);
+        assert_eq!(idx.lower_bound(&10), Ok(0));
+        assert_eq!(idx.upper_bound(&10), Ok(2));
+        assert_eq!(idx.upper_bound(&30), Err(4));
+
+        assert_eq!(idx.insert(2, 25), Some(10));
+        assert_eq!(idx.range(15..=25).map(|(_, &k)| k).collect::<Vec<_>>(), vec![3, 2]);
+        assert_eq!(idx.remove(&3), Some(20));
+        assert_eq!(idx.remove(&3), None);
+        assert_eq!(idx.get(&2), Some(&25));
+        assert_eq!(idx.get_index(1), Some((&25, &2)));
+        assert_eq!(idx.len(), 3);
+        assert_eq!(idx.range(40..).count(), 0);
+
+        // derived key: index records by the length of their name
+        let names = ["ab", "abcd", "a"];
+        let by_len = SecondaryIndex::from_entries(names.iter().enumerate().map(|(i, s)| (i, s.len())));
+        assert_eq!(by_len.iter().map(|(_, &i)| i).collect::<Vec<_>>(), vec![2, 0, 1]);
+    }
+}
@@ -0,0 +1,215 @@
+use crate::range_index;
+use semver::{Comparator, Op, Prerelease, Version, VersionReq};
+use std::ops::{Bound, Range};
+
+/// `VersionReq` queries over a sorted slice of `semver::Version`s.
+///
+/// Every comparator of a requirement bounds an interval of versions, so the candidates form one
+/// contiguous span found with two binary searches. The span can still hold pre-releases the
+/// requirement rejects: `^1.2` spans `1.5.0-beta`, but a pre-release only matches when a
+/// comparator names the same `major.minor.patch` with a pre-release of its own. The other
+/// methods apply `VersionReq::matches` to the span, so they follow those rules exactly.
+/// ```
+/// # use bound_stl::SemverSearch;
+/// # use semver::{Version, VersionReq};
+/// let versions = ["0.9.1", "1.1.0", "1.2.0", "1.2.7", "1.5.0-beta", "1.5.0", "2.0.0"]
+///     .map(|v| Version::parse(v).unwrap());
+/// let req = VersionReq::parse("^1.2").unwrap();
+/// assert_eq!(versions.req_span(&req), 2..6);
+/// let matching = versions.matching(&req).map(|(i, _)| i).collect::<Vec<_>>();
+/// assert_eq!(matching, [2, 3, 5]);
+/// assert_eq!(versions.max_satisfying(&req), Some(5));
+///
+/// let beta = VersionReq::parse(">=1.5.0-alpha, <1.5.0").unwrap();
+/// assert_eq!(versions.max_satisfying(&beta), Some(4));
+/// ```
+pub trait SemverSearch {
+    /// Index span of the versions within the bounds of `req`; see the trait docs.
+    fn req_span(&self, req: &VersionReq) -> Range<usize>;
+
+    /// The versions matching `req`, with their indices, in order.
+    fn matching<'a>(&'a self, req: &'a VersionReq) -> impl DoubleEndedIterator<Item = (usize, &'a Version)>;
+
+    /// Index of the greatest version matching `req`.
+    fn max_satisfying(&self, req: &VersionReq) -> Option<usize>;
+}
+
+impl SemverSearch for [Version] {
+    fn req_span(&self, req: &VersionReq) -> Range<usize> {
+        let (mut lo, mut hi) = (Bound::Unbounded, Bound::Unbounded);
+        for c in &req.comparators {
+            let (l, h) = comparator_bounds(c);
+            lo = tighter(lo, l, true);
+            hi = tighter(hi, h, false);
+        }
+        range_index(self.len(), (lo, hi), |i, v| self[i].cmp(v))
+    }
+
+    fn matching<'a>(&'a self, req: &'a VersionReq) -> impl DoubleEndedIterator<Item = (usize, &'a Version)> {
+        let span = self.req_span(req);
+        self[span.clone()]
+            .iter()
+            .zip(span)
+            .filter(|(v, _)| req.matches(v))
+            .map(|(v, i)| (i, v))
+    }
+
+    fn max_satisfying(&self, req: &VersionReq) -> Option<usize> {
+        self.matching(req).next_back().map(|(i, _)| i)
+    }
+}
+
+/// The interval of versions `c` can match. Lower bounds are taken at the `-0` pre-release,
+/// the least version of a `major.minor.patch`, so they never exclude a match.
+fn comparator_bounds(c: &Comparator) -> (Bound<Version>, Bound<Version>) {
+    let lowest = |major, minor, patch| Version {
+        pre: Prerelease::new("0").unwrap(),
+        ..Version::new(major, minor, patch)
+    };
+    let (major, minor, patch) = (c.major, c.minor.unwrap_or(0), c.patch.unwrap_or(0));
+    let exact = Version {
+        pre: c.pre.clone(),
+        ..Version::new(major, minor, patch)
+    };
+    let floor = if c.pre.is_empty() {
+        lowest(major, minor, patch)
+    } else {
+        exact.clone()
+    };
+    // the least version past every version with the given prefix
+    let next = |major: u64, minor: Option<u64>, patch: Option<u64>| match (minor, patch) {
+        (Some(minor), Some(patch)) => patch.checked_add(1).map(|p| lowest(major, minor, p)),
+        (Some(minor), None) => minor.checked_add(1).map(|m| lowest(major, m, 0)),
+        _ => major.checked_add(1).map(|m| lowest(m, 0, 0)),
+    };
+    let below = |v: Option<Version>| v.map_or(Bound::Unbounded, Bound::Excluded);
+    match c.op {
+        Op::Exact | Op::Wildcard => (Bound::Included(floor), below(next(major, c.minor, c.patch))),
+        Op::Greater => match next(major, c.minor, c.patch) {
+            _ if c.patch.is_some() => (Bound::Excluded(exact), Bound::Unbounded),
+            Some(v) => (Bound::Included(v), Bound::Unbounded),
+            // nothing lies past the greatest major
+            None => (Bound::Excluded(exact.clone()), Bound::Included(exact)),
+        },
+        Op::GreaterEq => (Bound::Included(floor), Bound::Unbounded),
+        Op::Less if c.patch.is_some() => (Bound::Unbounded, Bound::Excluded(exact)),
+        Op::Less => (Bound::Unbounded, Bound::Excluded(floor)),
+        Op::LessEq => (Bound::Unbounded, below(next(major, c.minor, c.patch))),
+        Op::Tilde => (Bound::Included(floor), below(next(major, c.minor, None))),
+        Op::Caret => {
+            let hi = match (major, c.minor, c.patch) {
+                (0, Some(0), Some(patch)) => next(0, Some(0), Some(patch)),
+                (0, Some(minor), _) => next(0, Some(minor), None),
+                _ => next(major, None, None),
+            };
+            (Bound::Included(floor), below(hi))
+        }
+        _ => (Bound::Unbounded, Bound::Unbounded),
+    }
+}
+
+/// The tighter of two lower (`lower == true`) or upper bounds.
+fn tighter(a: Bound<Version>, b: Bound<Version>, lower: bool) -> Bound<Version> {
+    let key = |b: &Bound<Version>| match b {
+        Bound::Included(v) | Bound::Excluded(v) => Some(v.clone()),
+        Bound::Unbounded => None,
+    };
+    match (key(&a), key(&b)) {
+        (None, _) => b,
+        (_, None) => a,
+        (Some(x), Some(y)) if x == y => match (&a, lower) {
+            (Bound::Excluded(_), _) => a,
+            _ => b,
+        },
+        (Some(x), Some(y)) => {
+            if (x < y) == lower {
+                b
+            } else {
+                a
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::SemverSearch;
+    use semver::{Version, VersionReq};
+
+    #[test]
+    fn test_semver_search() {
+        let mut versions = [
+            "0.0.1",
+            "0.0.2",
+            "0.1.0",
+            "0.1.5",
+            "0.2.0-rc.1",
+            "0.2.0",
+            "1.0.0-alpha",
+            "1.0.0-alpha.2",
+            "1.0.0-beta",
+            "1.0.0",
+            "1.0.1",
+            "1.2.0",
+            "1.2.3-pre",
+            "1.2.3",
+            "1.2.4",
+            "1.3.0",
+            "1.9.9",
+            "2.0.0-0",
+            "2.0.0",
+            "3.1.4",
+        ]
+        .map(|v| Version::parse(v).unwrap());
+        versions.sort();
+        let reqs = [
+            "*",
+            "1",
+            "1.2",
+            "=1.2.3",
+            "=1.2.3-pre",
+            ">1",
+            ">1.2",
+            ">1.2.3",
+            ">=1.2.3-pre",
+            "<1",
+            "<1.2",
+            "<1.2.3",
+            "<2.0.0-0",
+            "<=1.2",
+            "<=1.2.3",
+            "~1",
+            "~1.2",
+            "~1.2.3-pre",
+            "^0",
+            "^0.0",
+            "^0.0.1",
+            "^0.1",
+            "^0.2.0-rc.1",
+            "^1.0.0-alpha",
+            "^1.2",
+            ">=1.0.0-alpha, <1.0.0",
+            ">1.0.0, <1.2.4",
+            ">=3, <2",
+            "1.x, >1.1",
+        ];
+        for r in reqs {
+            let req = VersionReq::parse(r).unwrap();
+            let expect = (0..versions.len())
+                .filter(|&i| req.matches(&versions[i]))
+                .collect::<Vec<_>>();
+            let span = versions.req_span(&req);
+            assert!(expect.iter().all(|i| span.contains(i)), "req = {r}");
+            assert_eq!(
+                versions.matching(&req).map(|(i, _)| i).collect::<Vec<_>>(),
+                expect,
+                "req = {r}"
+            );
+            assert_eq!(versions.max_satisfying(&req), expect.last().copied(), "req = {r}");
+        }
+        assert_eq!(versions.req_span(&VersionReq::parse("~1.2").unwrap()), 11..15);
+        assert_eq!(versions.req_span(&VersionReq::parse("^0.0.1").unwrap()), 0..1);
+        assert!(versions.req_span(&VersionReq::parse(">=3, <2").unwrap()).is_empty());
+        assert_eq!([].req_span(&VersionReq::STAR), 0..0);
+    }
+}
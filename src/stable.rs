@@ -0,0 +1,363 @@
+use crate::{LowerBound, UpperBound};
+use std::cmp::Ordering;
+
+/// A stable reference to an element of a [`StableSortedVec`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Handle {
+    id: u32,
+    generation: u32,
+}
+
+#[derive(Debug, Clone)]
+struct Slot {
+    generation: u32,
+    /// physical position while the handle is live
+    pos: Option<usize>,
+}
+
+/// Prefix counts of live positions.
+#[derive(Debug, Clone)]
+struct Fenwick {
+    tree: Vec<usize>,
+}
+
+impl Fenwick {
+    fn new(live: impl ExactSizeIterator<Item = bool>) -> Self {
+        let mut tree = vec![0; live.len() + 1];
+        for (i, b) in live.enumerate() {
+            tree[i + 1] += b as usize;
+            let parent = (i + 1) + ((i + 1) & (i + 1).wrapping_neg());
+            if parent < tree.len() {
+                tree[parent] += tree[i + 1];
+            }
+        }
+        Fenwick { tree }
+    }
+
+    fn add(&mut self, pos: usize, delta: isize) {
+        let mut i = pos + 1;
+        while i < self.tree.len() {
+            self.tree[i] = self.tree[i].wrapping_add_signed(delta);
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    /// Live positions in `[0, pos)`.
+    fn prefix(&self, pos: usize) -> usize {
+        let (mut i, mut sum) = (pos, 0);
+        while i > 0 {
+            sum += self.tree[i];
+            i &= i - 1;
+        }
+        sum
+    }
+
+    /// The position of the live element of rank `k`, if any.
+    fn select(&self, mut k: usize) -> Option<usize> {
+        let mut pos = 0;
+        let mut step = (self.tree.len() - 1).checked_next_power_of_two()?;
+        while step > 0 {
+            if pos + step < self.tree.len() && self.tree[pos + step] <= k {
+                pos += step;
+                k -= self.tree[pos];
+            }
+            step /= 2;
+        }
+        (pos < self.tree.len() - 1).then_some(pos)
+    }
+}
+
+/// A sorted `Vec` whose elements are addressed by [`Handle`]s that survive other insertions
+/// and removals.
+///
+/// Removal only marks the element as a tombstone, which keeps its value for the binary searches
+/// and is reused by a later insertion next to it; once tombstones outnumber live elements the
+/// vector is compacted. Handles stay valid
+/// through all of this, while positions, including those returned by `lower_bound` and
+/// `upper_bound`, are ranks in the live view and shift as usual.
+/// ```
+/// # use bound_stl::{LowerBound, StableSortedVec};
+/// let mut v = StableSortedVec::new();
+/// let h30 = v.insert(30);
+/// let h10 = v.insert(10);
+/// let h20 = v.insert(20);
+/// assert_eq!(v.position(h30), Some(2));
+///
+/// assert!(v.remove(h10));
+/// assert_eq!(v.get(h10), None);
+/// assert_eq!(v.position(h30), Some(1));
+/// assert_eq!(v.lower_bound(&25), Ok(1));
+/// assert_eq!(v.get(h20), Some(&20));
+/// ```
+#[derive(Debug, Clone)]
+pub struct StableSortedVec<T> {
+    /// all elements in sorted order, tombstones included
+    values: Vec<T>,
+    /// the handle id owning each position, `None` for a tombstone
+    owners: Vec<Option<u32>>,
+    live: Fenwick,
+    slots: Vec<Slot>,
+    free: Vec<u32>,
+}
+
+impl<T: Ord> Default for StableSortedVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord> StableSortedVec<T> {
+    pub fn new() -> Self {
+        StableSortedVec {
+            values: Vec::new(),
+            owners: Vec::new(),
+            live: Fenwick::new(std::iter::empty()),
+            slots: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+
+    /// Number of live elements.
+    pub fn len(&self) -> usize {
+        self.live.prefix(self.values.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of removed elements still taking up space.
+    pub fn tombstones(&self) -> usize {
+        self.values.len() - self.len()
+    }
+
+    /// Inserts `value` after the live elements equal to it.
+    ///
+    /// Panics if `u32::MAX` handles are already in use.
+    pub fn insert(&mut self, value: T) -> Handle {
+        let handle = self.new_handle();
+        let p = self.values.partition_point(|x| x <= &value);
+        // a tombstone right before or at the insertion point can take the value in place
+        let pos = if p > 0 && self.owners[p - 1].is_none() {
+            Some(p - 1)
+        } else if p < self.values.len() && self.owners[p].is_none() {
+            Some(p)
+        } else {
+            None
+        };
+        match pos {
+            Some(pos) => {
+                self.values[pos] = value;
+                self.owners[pos] = Some(handle.id);
+                self.slots[handle.id as usize].pos = Some(pos);
+                self.live.add(pos, 1);
+            }
+            None => {
+                self.values.insert(p, value);
+                self.owners.insert(p, Some(handle.id));
+                self.reindex(p);
+                self.live = Fenwick::new(self.owners.iter().map(Option::is_some));
+            }
+        }
+        handle
+    }
+
+    /// Removes the element of `handle`, invalidating it; returns `false` if it was already gone.
+    pub fn remove(&mut self, handle: Handle) -> bool {
+        let Some(pos) = self.pos(handle) else {
+            return false;
+        };
+        let slot = &mut self.slots[handle.id as usize];
+        slot.pos = None;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(handle.id);
+        self.owners[pos] = None;
+        self.live.add(pos, -1);
+        if self.tombstones() > self.len() {
+            self.compact();
+        }
+        true
+    }
+
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        self.pos(handle).map(|pos| &self.values[pos])
+    }
+
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.pos(handle).is_some()
+    }
+
+    /// Rank of the element of `handle` in the live view.
+    pub fn position(&self, handle: Handle) -> Option<usize> {
+        self.pos(handle).map(|pos| self.live.prefix(pos))
+    }
+
+    /// The live element of rank `idx` and its handle.
+    pub fn get_index(&self, idx: usize) -> Option<(Handle, &T)> {
+        let pos = self.live.select(idx)?;
+        Some((self.handle_at(pos), &self.values[pos]))
+    }
+
+    /// Live elements in order, with their handles.
+    pub fn iter(&self) -> impl Iterator<Item = (Handle, &T)> {
+        (0..self.values.len())
+            .filter(|&pos| self.owners[pos].is_some())
+            .map(|pos| (self.handle_at(pos), &self.values[pos]))
+    }
+
+    /// Drops all tombstones; handles stay valid.
+    pub fn compact(&mut self) {
+        let mut kept = 0;
+        for pos in 0..self.values.len() {
+            if self.owners[pos].is_some() {
+                self.values.swap(kept, pos);
+                self.owners.swap(kept, pos);
+                kept += 1;
+            }
+        }
+        self.values.truncate(kept);
+        self.owners.truncate(kept);
+        self.reindex(0);
+        self.live = Fenwick::new(self.owners.iter().map(Option::is_some));
+    }
+
+    fn new_handle(&mut self) -> Handle {
+        let id = self.free.pop().unwrap_or_else(|| {
+            let id = u32::try_from(self.slots.len()).expect("handle ids exhausted");
+            self.slots.push(Slot {
+                generation: 0,
+                pos: None,
+            });
+            id
+        });
+        Handle {
+            id,
+            generation: self.slots[id as usize].generation,
+        }
+    }
+
+    fn pos(&self, handle: Handle) -> Option<usize> {
+        let slot = self.slots.get(handle.id as usize)?;
+        (slot.generation == handle.generation).then_some(slot.pos)?
+    }
+
+    fn handle_at(&self, pos: usize) -> Handle {
+        let id = self.owners[pos].expect("tombstone has no handle");
+        Handle {
+            id,
+            generation: self.slots[id as usize].generation,
+        }
+    }
+
+    /// Points the handles of the elements from `from` on at their current positions.
+    fn reindex(&mut self, from: usize) {
+        for pos in from..self.owners.len() {
+            if let Some(id) = self.owners[pos] {
+                self.slots[id as usize].pos = Some(pos);
+            }
+        }
+    }
+
+    /// Live rank of physical bound `pos`, with the `Result` convention.
+    fn live_bound(&self, pos: usize) -> Result<usize, usize> {
+        let rank = self.live.prefix(pos);
+        if rank == self.len() {
+            Err(rank)
+        } else {
+            Ok(rank)
+        }
+    }
+}
+
+impl<T: Ord> LowerBound for StableSortedVec<T> {
+    type Item = T;
+    fn lower_bound(&self, x: &Self::Item) -> Result<usize, usize> {
+        self.lower_bound_by(|y| y.cmp(x))
+    }
+
+    fn lower_bound_by<'a, F>(&'a self, f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> Ordering,
+    {
+        self.live_bound(self.values.lower_bound_by(f).unwrap_or_else(|e| e))
+    }
+
+    fn lower_bound_by_key<'a, K, F>(&'a self, k: &K, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> K,
+        K: Ord,
+    {
+        self.lower_bound_by(|y| f(y).cmp(k))
+    }
+}
+
+impl<T: Ord> UpperBound for StableSortedVec<T> {
+    type Item = T;
+    fn upper_bound(&self, x: &Self::Item) -> Result<usize, usize> {
+        self.upper_bound_by(|y| y.cmp(x))
+    }
+
+    fn upper_bound_by<'a, F>(&'a self, f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> Ordering,
+    {
+        self.live_bound(self.values.upper_bound_by(f).unwrap_or_else(|e| e))
+    }
+
+    fn upper_bound_by_key<'a, K, F>(&'a self, k: &K, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> K,
+        K: Ord,
+    {
+        self.upper_bound_by(|y| f(y).cmp(k))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::{LowerBound, StableSortedVec, UpperBound};
+
+    #[test]
+    fn test_stable_sorted_vec() {
+        // mirror every operation on a plain sorted Vec of (value, insertion number)
+        assert_eq!(StableSortedVec::<i32>::new().get_index(0), None);
+        let mut v = StableSortedVec::new();
+        let mut model: Vec<(u32, usize)> = Vec::new();
+        let mut handles = Vec::new();
+        let mut seed = 1u32;
+        for step in 0..600 {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            let r = seed >> 16;
+            if !r.is_multiple_of(3) || model.is_empty() {
+                let x = r % 50;
+                handles.push((v.insert(x), x, step));
+                let i = model.partition_point(|&(y, _)| y <= x);
+                model.insert(i, (x, step));
+            } else {
+                let (h, x, n) = handles.swap_remove(r as usize % handles.len());
+                assert!(v.remove(h));
+                assert!(!v.remove(h));
+                assert_eq!(v.get(h), None);
+                model.retain(|&e| e != (x, n));
+            }
+            assert!(v.tombstones() <= v.len() || v.is_empty());
+        }
+        let values = model.iter().map(|&(x, _)| x).collect::<Vec<_>>();
+        assert!(v.iter().map(|(_, &x)| x).eq(values.iter().copied()));
+        for &(h, x, n) in &handles {
+            assert_eq!(v.get(h), Some(&x));
+            let rank = v.position(h).unwrap();
+            assert_eq!(model[rank], (x, n));
+            assert_eq!(v.get_index(rank), Some((h, &x)));
+        }
+        assert_eq!(v.get_index(v.len()), None);
+        for x in 0..52 {
+            assert_eq!(v.lower_bound(&x), values.lower_bound(&x));
+            assert_eq!(v.upper_bound(&x), values.upper_bound(&x));
+        }
+        let before = handles.iter().map(|&(h, ..)| v.position(h)).collect::<Vec<_>>();
+        v.compact();
+        assert_eq!(v.tombstones(), 0);
+        assert!(handles.iter().map(|&(h, ..)| v.position(h)).eq(before));
+    }
+}
@@ -0,0 +1,309 @@
+use crate::{lower_bound_index, range_index, upper_bound_index, LowerBound, UpperBound};
+use std::cmp::Ordering;
+use std::ops::RangeBounds;
+
+/// A map over a `'static` slice of pairs sorted by unique keys, usually built by [`sorted_map!`].
+/// ```
+/// # use bound_stl::{sorted_map, LowerBound};
+/// static KEYWORDS: bound_stl::StaticSortedMap<&str, u8> = sorted_map! {
+///     "while" => 4, "fn" => 1, "let" => 2, "match" => 3,
+/// };
+/// assert_eq!(KEYWORDS.get("let"), Some(&2));
+/// assert_eq!(KEYWORDS.get("loop"), None);
+/// assert_eq!(KEYWORDS.lower_bound(&"l"), Ok(1));
+/// let m = KEYWORDS.range("m".."n").map(|(k, _)| *k).collect::<Vec<_>>();
+/// assert_eq!(m, ["match"]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StaticSortedMap<K: 'static, V: 'static> {
+    entries: &'static [(K, V)],
+}
+
+impl<K: Ord, V> StaticSortedMap<K, V> {
+    /// Wraps `entries`, which must be sorted by unique keys.
+    pub const fn new(entries: &'static [(K, V)]) -> Self {
+        StaticSortedMap { entries }
+    }
+
+    pub const fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub const fn as_slice(&self) -> &'static [(K, V)] {
+        self.entries
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&'static V>
+    where
+        K: std::borrow::Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let i = self.entries.binary_search_by(|(k, _)| k.borrow().cmp(key)).ok()?;
+        Some(&self.entries[i].1)
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: std::borrow::Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+
+    /// The pairs whose key lies in `range`.
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> std::slice::Iter<'static, (K, V)> {
+        self.entries[range_index(self.entries.len(), range, |i, k| self.entries[i].0.cmp(k))].iter()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'static, (K, V)> {
+        self.entries.iter()
+    }
+}
+
+/// A set over a `'static` sorted slice of unique keys, usually built by [`sorted_set!`].
+/// ```
+/// # use bound_stl::sorted_set;
+/// let reserved = sorted_set!["self", "crate", "super"];
+/// assert!(reserved.contains("super"));
+/// assert_eq!(reserved.as_slice(), ["crate", "self", "super"]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StaticSortedSet<K: 'static> {
+    keys: &'static [K],
+}
+
+impl<K: Ord> StaticSortedSet<K> {
+    /// Wraps `keys`, which must be sorted and unique.
+    pub const fn new(keys: &'static [K]) -> Self {
+        StaticSortedSet { keys }
+    }
+
+    pub const fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub const fn as_slice(&self) -> &'static [K] {
+        self.keys
+    }
+
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: std::borrow::Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.keys.binary_search_by(|k| k.borrow().cmp(key)).is_ok()
+    }
+
+    /// The keys in `range`.
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> &'static [K] {
+        &self.keys[range_index(self.keys.len(), range, |i, k| self.keys[i].cmp(k))]
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'static, K> {
+        self.keys.iter()
+    }
+}
+
+/// Builds a [`StaticSortedMap`] with `&'static str` keys, sorted at compile time.
+///
+/// The pairs are sorted in a const block, so the map is a promoted `'static` array with no
+/// runtime initialisation; a duplicate key is a compile error. Values must be constant
+/// expressions of a `Copy` type. See [`StaticSortedMap`] for an example.
+/// ```compile_fail
+/// let map = bound_stl::sorted_map! { "a" => 1, "b" => 2, "a" => 3 };
+/// ```
+#[macro_export]
+macro_rules! sorted_map {
+    ($($k:expr => $v:expr),* $(,)?) => {
+        $crate::StaticSortedMap::new(&const { $crate::__sort_map([$(($k, $v)),*]) })
+    };
+}
+
+/// Builds a [`StaticSortedSet`] of `&'static str`, sorted at compile time like [`sorted_map!`].
+#[macro_export]
+macro_rules! sorted_set {
+    ($($k:expr),* $(,)?) => {
+        $crate::StaticSortedSet::new(&const { $crate::__sort_set([$($k),*]) })
+    };
+}
+
+#[doc(hidden)]
+pub const fn __sort_map<V: Copy, const N: usize>(mut entries: [(&'static str, V); N]) -> [(&'static str, V); N] {
+    let mut i = 1;
+    while i < N {
+        let mut j = i;
+        while j > 0 && str_cmp(entries[j - 1].0, entries[j].0) > 0 {
+            let e = entries[j];
+            entries[j] = entries[j - 1];
+            entries[j - 1] = e;
+            j -= 1;
+        }
+        assert!(
+            j == 0 || str_cmp(entries[j - 1].0, entries[j].0) < 0,
+            "duplicate key in sorted_map!"
+        );
+        i += 1;
+    }
+    entries
+}
+
+#[doc(hidden)]
+pub const fn __sort_set<const N: usize>(mut keys: [&'static str; N]) -> [&'static str; N] {
+    let mut i = 1;
+    while i < N {
+        let mut j = i;
+        while j > 0 && str_cmp(keys[j - 1], keys[j]) > 0 {
+            keys.swap(j - 1, j);
+            j -= 1;
+        }
+        assert!(
+            j == 0 || str_cmp(keys[j - 1], keys[j]) < 0,
+            "duplicate key in sorted_set!"
+        );
+        i += 1;
+    }
+    keys
+}
+
+/// `Ord::cmp` for `str` in const context, as -1, 0 or 1.
+const fn str_cmp(a: &str, b: &str) -> i8 {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let mut i = 0;
+    while i < a.len() && i < b.len() {
+        if a[i] != b[i] {
+            return if a[i] < b[i] { -1 } else { 1 };
+        }
+        i += 1;
+    }
+    if a.len() < b.len() {
+        -1
+    } else if a.len() > b.len() {
+        1
+    } else {
+        0
+    }
+}
+
+impl<K: Ord, V> LowerBound for StaticSortedMap<K, V> {
+    type Item = K;
+    fn lower_bound(&self, x: &Self::Item) -> Result<usize, usize> {
+        self.lower_bound_by(|y| y.cmp(x))
+    }
+
+    fn lower_bound_by<'a, F>(&'a self, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> Ordering,
+    {
+        lower_bound_index(self.entries.len(), |i| f(&self.entries[i].0))
+    }
+
+    fn lower_bound_by_key<'a, B, F>(&'a self, b: &B, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> B,
+        B: Ord,
+    {
+        self.lower_bound_by(|y| f(y).cmp(b))
+    }
+}
+
+impl<K: Ord, V> UpperBound for StaticSortedMap<K, V> {
+    type Item = K;
+    fn upper_bound(&self, x: &Self::Item) -> Result<usize, usize> {
+        self.upper_bound_by(|y| y.cmp(x))
+    }
+
+    fn upper_bound_by<'a, F>(&'a self, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> Ordering,
+    {
+        upper_bound_index(self.entries.len(), |i| f(&self.entries[i].0))
+    }
+
+    fn upper_bound_by_key<'a, B, F>(&'a self, b: &B, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> B,
+        B: Ord,
+    {
+        self.upper_bound_by(|y| f(y).cmp(b))
+    }
+}
+
+impl<K: Ord> LowerBound for StaticSortedSet<K> {
+    type Item = K;
+    fn lower_bound(&self, x: &Self::Item) -> Result<usize, usize> {
+        self.keys.lower_bound(x)
+    }
+
+    fn lower_bound_by<'a, F>(&'a self, f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> Ordering,
+    {
+        self.keys.lower_bound_by(f)
+    }
+
+    fn lower_bound_by_key<'a, B, F>(&'a self, b: &B, f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> B,
+        B: Ord,
+    {
+        self.keys.lower_bound_by_key(b, f)
+    }
+}
+
+impl<K: Ord> UpperBound for StaticSortedSet<K> {
+    type Item = K;
+    fn upper_bound(&self, x: &Self::Item) -> Result<usize, usize> {
+        self.keys.upper_bound(x)
+    }
+
+    fn upper_bound_by<'a, F>(&'a self, f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> Ordering,
+    {
+        self.keys.upper_bound_by(f)
+    }
+
+    fn upper_bound_by_key<'a, B, F>(&'a self, b: &B, f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> B,
+        B: Ord,
+    {
+        self.keys.upper_bound_by_key(b, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::{LowerBound, StaticSortedMap, UpperBound};
+
+    const EMPTY: StaticSortedMap<&str, ()> = bound_stl::sorted_map! {};
+
+    #[test]
+    fn test_sorted_map_macro() {
+        let map = bound_stl::sorted_map! { "b" => 'b', "" => '0', "ab" => 'x', "a" => 'a', "ba" => 'y' };
+        let keys = map.iter().map(|(k, _)| *k).collect::<Vec<_>>();
+        assert_eq!(keys, ["", "a", "ab", "b", "ba"]);
+        assert_eq!(map.get("ab"), Some(&'x'));
+        assert!(map.contains_key(""));
+        assert!(!map.contains_key("c"));
+        for q in ["", "a", "aa", "b", "bb"] {
+            assert_eq!(map.lower_bound(&q), keys.lower_bound(&q));
+            assert_eq!(map.upper_bound(&q), keys.upper_bound(&q));
+        }
+        assert_eq!(map.range("a"..="b").count(), 3);
+        assert!(EMPTY.is_empty());
+
+        let set = bound_stl::sorted_set!["delta", "alpha", "charlie", "bravo",];
+        assert_eq!(set.range(.."c"), ["alpha", "bravo"]);
+        assert_eq!(set.upper_bound(&"charlie"), Ok(3));
+        assert_eq!(set.len(), 4);
+    }
+}
@@ -0,0 +1,241 @@
+use crate::{lower_bound_index, range_index, upper_bound_index, LowerBound, UpperBound};
+use std::cmp::Ordering;
+use std::ops::{Bound, Range, RangeBounds};
+
+/// A piecewise-constant map: a value below every breakpoint, then one value per breakpoint
+/// holding from that key up to the next one.
+///
+/// `get(&k)` is the value of the last breakpoint not greater than `k`, found with an
+/// `upper_bound` over the breakpoints, or the initial value when `k` precedes them all.
+/// The bound methods search the breakpoints and follow the usual `Result` convention.
+/// ```
+/// # use bound_stl::StepMap;
+/// // tiered pricing: 10 per unit, 8 from 100 units, 5 from 1000
+/// let mut price = StepMap::new(10);
+/// price.insert(100, 8);
+/// price.insert(1000, 5);
+/// assert_eq!(price.get(&99), &10);
+/// assert_eq!(price.get(&100), &8);
+/// assert_eq!(price.get(&5000), &5);
+///
+/// // a promotion for orders of 500..2000 units
+/// price.assign(500..2000, 4);
+/// let steps = price.iter().map(|(k, v)| (k.copied(), *v)).collect::<Vec<_>>();
+/// assert_eq!(steps, [(None, 10), (Some(100), 8), (Some(500), 4), (Some(2000), 5)]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StepMap<K, V> {
+    initial: V,
+    breaks: Vec<(K, V)>,
+}
+
+impl<K: Ord, V> StepMap<K, V> {
+    /// A map that is `initial` everywhere.
+    pub fn new(initial: V) -> Self {
+        StepMap {
+            initial,
+            breaks: Vec::new(),
+        }
+    }
+
+    /// Number of breakpoints.
+    pub fn len(&self) -> usize {
+        self.breaks.len()
+    }
+
+    /// Whether the map is constant.
+    pub fn is_empty(&self) -> bool {
+        self.breaks.is_empty()
+    }
+
+    /// The value below the first breakpoint.
+    pub fn initial(&self) -> &V {
+        &self.initial
+    }
+
+    pub fn breakpoints(&self) -> &[(K, V)] {
+        &self.breaks
+    }
+
+    /// Index of the breakpoint whose step contains `key`, `None` for the initial step.
+    pub fn step_index(&self, key: &K) -> Option<usize> {
+        self.upper_bound(key).unwrap_or_else(|e| e).checked_sub(1)
+    }
+
+    pub fn get(&self, key: &K) -> &V {
+        match self.step_index(key) {
+            Some(i) => &self.breaks[i].1,
+            None => &self.initial,
+        }
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> &mut V {
+        match self.step_index(key) {
+            Some(i) => &mut self.breaks[i].1,
+            None => &mut self.initial,
+        }
+    }
+
+    /// Splits the step containing `key` so that `value` holds from `key` up to the next
+    /// breakpoint, and returns the value replaced if `key` already was a breakpoint.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.lower_bound(&key) {
+            Ok(i) if self.breaks[i].0 == key => Some(std::mem::replace(&mut self.breaks[i].1, value)),
+            pos => {
+                self.breaks.insert(pos.unwrap_or_else(|e| e), (key, value));
+                None
+            }
+        }
+    }
+
+    /// Removes the breakpoint at `key`, merging its step into the previous one.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        match self.lower_bound(key) {
+            Ok(i) if &self.breaks[i].0 == key => Some(self.breaks.remove(i).1),
+            _ => None,
+        }
+    }
+
+    /// Sets the value on `range`, splitting the steps at its ends; values outside it are kept.
+    pub fn assign(&mut self, range: Range<K>, value: V)
+    where
+        V: Clone,
+    {
+        if range.start >= range.end {
+            return;
+        }
+        let lo = self.lower_bound(&range.start).unwrap_or_else(|e| e);
+        let hi = self.lower_bound(&range.end).unwrap_or_else(|e| e);
+        // the value that must resume at `range.end` unless a breakpoint is already there
+        let resume = (self.breaks.get(hi).map(|(k, _)| k) != Some(&range.end)).then(|| self.get(&range.end).clone());
+        let mut tail = vec![(range.start, value)];
+        tail.extend(resume.map(|v| (range.end, v)));
+        self.breaks.splice(lo..hi, tail);
+    }
+
+    /// Removes breakpoints that do not change the value.
+    pub fn coalesce(&mut self)
+    where
+        V: PartialEq,
+    {
+        let mut kept: Vec<(K, V)> = Vec::with_capacity(self.breaks.len());
+        for (k, v) in std::mem::take(&mut self.breaks) {
+            if kept.last().map_or(&self.initial, |(_, last)| last) != &v {
+                kept.push((k, v));
+            }
+        }
+        self.breaks = kept;
+    }
+
+    /// Every step as its starting breakpoint and value; the initial step starts at `None`.
+    pub fn iter(&self) -> impl Iterator<Item = (Option<&K>, &V)> {
+        std::iter::once((None, &self.initial)).chain(self.breaks.iter().map(|(k, v)| (Some(k), v)))
+    }
+
+    /// The steps overlapping `range`, in the form of [`StepMap::iter`].
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> impl Iterator<Item = (Option<&K>, &V)> {
+        // the step containing the start overlaps, and so does every step starting inside the range
+        let first = match range.start_bound() {
+            Bound::Included(k) | Bound::Excluded(k) => self.step_index(k),
+            Bound::Unbounded => None,
+        };
+        let before_end = (Bound::Unbounded, range.end_bound());
+        let end = range_index(self.breaks.len(), before_end, |i, k| self.breaks[i].0.cmp(k)).end;
+        let head = match first {
+            Some(i) => (Some(&self.breaks[i].0), &self.breaks[i].1),
+            None => (None, &self.initial),
+        };
+        let start = first.map_or(0, |i| i + 1);
+        let rest = self.breaks[..end].iter().skip(start);
+        std::iter::once(head).chain(rest.map(|(k, v)| (Some(k), v)))
+    }
+}
+
+impl<K: Ord, V> LowerBound for StepMap<K, V> {
+    type Item = K;
+    fn lower_bound(&self, x: &Self::Item) -> Result<usize, usize> {
+        self.lower_bound_by(|y| y.cmp(x))
+    }
+
+    fn lower_bound_by<'a, F>(&'a self, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> Ordering,
+    {
+        lower_bound_index(self.breaks.len(), |i| f(&self.breaks[i].0))
+    }
+
+    fn lower_bound_by_key<'a, B, F>(&'a self, b: &B, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> B,
+        B: Ord,
+    {
+        self.lower_bound_by(|y| f(y).cmp(b))
+    }
+}
+
+impl<K: Ord, V> UpperBound for StepMap<K, V> {
+    type Item = K;
+    fn upper_bound(&self, x: &Self::Item) -> Result<usize, usize> {
+        self.upper_bound_by(|y| y.cmp(x))
+    }
+
+    fn upper_bound_by<'a, F>(&'a self, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> Ordering,
+    {
+        upper_bound_index(self.breaks.len(), |i| f(&self.breaks[i].0))
+    }
+
+    fn upper_bound_by_key<'a, B, F>(&'a self, b: &B, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a Self::Item) -> B,
+        B: Ord,
+    {
+        self.upper_bound_by(|y| f(y).cmp(b))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::StepMap;
+
+    #[test]
+    fn test_step_map() {
+        // compare against a plain array of values over 0..30
+        let mut map = StepMap::new('a');
+        let mut dense = ['a'; 30];
+        for (range, v) in [
+            (5..10, 'b'),
+            (8..20, 'c'),
+            (0..3, 'd'),
+            (12..14, 'a'),
+            (19..25, 'e'),
+            (9..12, 'e'),
+        ] {
+            map.assign(range.clone(), v);
+            dense[range].fill(v);
+            for (k, v) in dense.iter().enumerate() {
+                assert_eq!(map.get(&k), v, "k = {k}");
+            }
+        }
+        let len = map.len();
+        map.assign(4..4, 'z');
+        map.insert(26, 'a');
+        map.coalesce();
+        assert_eq!(map.len(), len);
+        for (k, v) in dense.iter().enumerate() {
+            assert_eq!(map.get(&k), v, "k = {k}");
+        }
+
+        let steps = map.range(10..13).map(|(k, v)| (k.copied(), *v)).collect::<Vec<_>>();
+        assert_eq!(steps, [(Some(9), 'e'), (Some(12), 'a')]);
+        assert_eq!(map.range(..0).count(), 1);
+
+        assert_eq!(map.remove(&25), Some('a'));
+        assert_eq!(map.remove(&25), None);
+        assert_eq!(map.get(&29), &'e');
+        assert_eq!(map.insert(0, 'x'), Some('d'));
+        *map.get_mut(&1) = 'y';
+        assert_eq!(map.get(&0), &'y');
+    }
+}
@@ -0,0 +1,163 @@
+use crate::{lower_bound_index, upper_bound_index};
+use std::cmp::Ordering;
+use std::ops::Range;
+
+/// A sorted table of fixed-size records packed in a byte buffer, searched in place.
+///
+/// Each record is `stride` bytes long and the table is sorted by the key bytes at
+/// `key_offset..key_offset + key_len` of every record, compared as with `memcmp`.
+/// Records are never copied out: the bound methods hand out sub-slices of the buffer.
+///
+/// The methods mirror `LowerBound` and `UpperBound` and follow the same `Result` convention,
+/// but they are inherent because a record is a borrowed `&[u8]` rather than an owned `Item`.
+/// The `_by` and `_by_key` variants receive the whole record, so a closure can decode any key.
+/// ```
+/// # use bound_stl::StridedSorted;
+/// // records of 4 bytes: a big-endian u16 key followed by a u16 payload
+/// let buf = [0, 1, 0xaa, 0xaa, 0, 3, 0xbb, 0xbb, 0, 3, 0xcc, 0xcc, 0, 9, 0xdd, 0xdd];
+/// let table = StridedSorted::new(&buf, 4, 0, 2);
+/// assert_eq!(table.len(), 4);
+/// assert_eq!(table.lower_bound(&[0, 3]), Ok(1));
+/// assert_eq!(table.upper_bound(&[0, 3]), Ok(3));
+/// assert_eq!(table.lower_bound(&[0, 10]), Err(4));
+/// assert_eq!(table.record(3), &[0, 9, 0xdd, 0xdd]);
+///
+/// let key = |r: &[u8]| u16::from_be_bytes([r[0], r[1]]);
+/// assert_eq!(table.lower_bound_by_key(&4, key), Ok(3));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct StridedSorted<'a> {
+    data: &'a [u8],
+    stride: usize,
+    key_offset: usize,
+    key_len: usize,
+}
+
+impl<'a> StridedSorted<'a> {
+    /// Panics if `stride` is zero, the buffer is not a whole number of records,
+    /// or the key does not fit inside a record.
+    pub fn new(data: &'a [u8], stride: usize, key_offset: usize, key_len: usize) -> Self {
+        assert!(stride > 0, "stride must not be zero");
+        assert_eq!(data.len() % stride, 0, "buffer length is not a multiple of the stride");
+        assert!(key_offset + key_len <= stride, "key does not fit inside a record");
+        StridedSorted {
+            data,
+            stride,
+            key_offset,
+            key_len,
+        }
+    }
+
+    /// A view whose key is the whole record.
+    pub fn whole_record(data: &'a [u8], stride: usize) -> Self {
+        Self::new(data, stride, 0, stride)
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len() / self.stride
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    pub fn record(&self, idx: usize) -> &'a [u8] {
+        &self.data[idx * self.stride..(idx + 1) * self.stride]
+    }
+
+    pub fn key(&self, idx: usize) -> &'a [u8] {
+        &self.record(idx)[self.key_offset..self.key_offset + self.key_len]
+    }
+
+    /// The records in `range`, as one contiguous byte slice.
+    pub fn records(&self, range: Range<usize>) -> &'a [u8] {
+        &self.data[range.start * self.stride..range.end * self.stride]
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &'a [u8]> + 'a {
+        self.data.chunks_exact(self.stride)
+    }
+
+    pub fn lower_bound(&self, key: &[u8]) -> Result<usize, usize> {
+        lower_bound_index(self.len(), |i| self.key(i).cmp(key))
+    }
+
+    pub fn lower_bound_by<F>(&self, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a [u8]) -> Ordering,
+    {
+        lower_bound_index(self.len(), |i| f(self.record(i)))
+    }
+
+    pub fn lower_bound_by_key<K, F>(&self, k: &K, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a [u8]) -> K,
+        K: Ord,
+    {
+        self.lower_bound_by(|r| f(r).cmp(k))
+    }
+
+    pub fn upper_bound(&self, key: &[u8]) -> Result<usize, usize> {
+        upper_bound_index(self.len(), |i| self.key(i).cmp(key))
+    }
+
+    pub fn upper_bound_by<F>(&self, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a [u8]) -> Ordering,
+    {
+        upper_bound_index(self.len(), |i| f(self.record(i)))
+    }
+
+    pub fn upper_bound_by_key<K, F>(&self, k: &K, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&'a [u8]) -> K,
+        K: Ord,
+    {
+        self.upper_bound_by(|r| f(r).cmp(k))
+    }
+
+    /// Index range of the records whose key equals `key`.
+    pub fn equal_range(&self, key: &[u8]) -> Range<usize> {
+        let lo = self.lower_bound(key).unwrap_or_else(|e| e);
+        let hi = self.upper_bound(key).unwrap_or_else(|e| e);
+        lo..hi
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::{LowerBound, StridedSorted, UpperBound};
+
+    #[test]
+    fn test_strided_sorted() {
+        let keys = [3u32, 5, 5, 8, 13, 21];
+        let mut buf = Vec::new();
+        for (i, k) in keys.iter().enumerate() {
+            buf.extend_from_slice(&(i as u16).to_le_bytes());
+            buf.extend_from_slice(&k.to_be_bytes());
+        }
+        let table = StridedSorted::new(&buf, 6, 2, 4);
+        for x in 0..25u32 {
+            let x_be = x.to_be_bytes();
+            assert_eq!(table.lower_bound(&x_be), keys.lower_bound(&x));
+            assert_eq!(table.upper_bound(&x_be), keys.upper_bound(&x));
+        }
+        assert_eq!(table.equal_range(&5u32.to_be_bytes()), 1..3);
+        assert_eq!(table.records(1..3).len(), 12);
+        assert_eq!(table.iter().count(), 6);
+
+        let empty = StridedSorted::whole_record(&[], 8);
+        assert!(empty.is_empty());
+        assert_eq!(empty.lower_bound(&[0; 8]), Err(0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_strided_sorted_ragged_buffer() {
+        StridedSorted::new(&[0; 7], 2, 0, 2);
+    }
+}
@@ -0,0 +1,189 @@
+use crate::range_index;
+use std::ops::{Range, RangeBounds};
+use std::time::{Duration, SystemTime};
+
+/// A record that carries a point in time; slices of such records sorted by that time
+/// can be windowed with [`TimeRange`].
+///
+/// It is implemented for the time types themselves (`SystemTime`, `Duration`, and with the
+/// `chrono` / `time` features their date-time types), so a plain sorted `Vec<SystemTime>`
+/// works out of the box. Implement it for your own structs to skip the `_by_key` closure.
+pub trait Timestamped {
+    type Time: Ord;
+    fn timestamp(&self) -> Self::Time;
+}
+
+impl Timestamped for SystemTime {
+    type Time = SystemTime;
+    fn timestamp(&self) -> Self::Time {
+        *self
+    }
+}
+
+/// `Duration` is used for offsets from a common origin, e.g. time since the start of a trace.
+impl Timestamped for Duration {
+    type Time = Duration;
+    fn timestamp(&self) -> Self::Time {
+        *self
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> Timestamped for chrono::DateTime<Tz> {
+    type Time = chrono::DateTime<Tz>;
+    fn timestamp(&self) -> Self::Time {
+        self.clone()
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Timestamped for chrono::NaiveDateTime {
+    type Time = chrono::NaiveDateTime;
+    fn timestamp(&self) -> Self::Time {
+        *self
+    }
+}
+
+#[cfg(feature = "time")]
+impl Timestamped for time::OffsetDateTime {
+    type Time = time::OffsetDateTime;
+    fn timestamp(&self) -> Self::Time {
+        *self
+    }
+}
+
+#[cfg(feature = "time")]
+impl Timestamped for time::PrimitiveDateTime {
+    type Time = time::PrimitiveDateTime;
+    fn timestamp(&self) -> Self::Time {
+        *self
+    }
+}
+
+/// Time-window search over a slice sorted by time; assume the slice is sorted.
+/// The `_by_key` variants take the time out of each element with a closure,
+/// so they also work for elements that do not implement [`Timestamped`].
+/// ```
+/// # use bound_stl::TimeRange;
+/// # use std::time::Duration;
+/// let v = vec![Duration::from_secs(1), Duration::from_secs(3), Duration::from_secs(5)];
+/// assert_eq!(v.range_of_time(Duration::from_secs(2)..Duration::from_secs(5)), 1..2);
+/// assert_eq!(v.range_of_time(Duration::from_secs(2)..=Duration::from_secs(5)), 1..3);
+/// assert_eq!(v.range_of_time(..), 0..3);
+/// ```
+pub trait TimeRange {
+    type Item;
+
+    /// Index range of the elements whose time lies in `range`.
+    fn range_of_time<R, T>(&self, range: R) -> Range<usize>
+    where
+        Self::Item: Timestamped<Time = T>,
+        T: Ord,
+        R: RangeBounds<T>;
+
+    /// Index range of the elements whose time, as extracted by `f`, lies in `range`.
+    fn range_of_time_by_key<R, K, F>(&self, range: R, f: F) -> Range<usize>
+    where
+        F: FnMut(&Self::Item) -> K,
+        K: Ord,
+        R: RangeBounds<K>;
+}
+
+impl<E> TimeRange for [E] {
+    type Item = E;
+
+    fn range_of_time<R, T>(&self, range: R) -> Range<usize>
+    where
+        E: Timestamped<Time = T>,
+        T: Ord,
+        R: RangeBounds<T>,
+    {
+        self.range_of_time_by_key(range, |e| e.timestamp())
+    }
+
+    fn range_of_time_by_key<R, K, F>(&self, range: R, mut f: F) -> Range<usize>
+    where
+        F: FnMut(&E) -> K,
+        K: Ord,
+        R: RangeBounds<K>,
+    {
+        range_index(self.len(), range, |i, t| f(&self[i]).cmp(t))
+    }
+}
+
+impl<E> TimeRange for Vec<E> {
+    type Item = E;
+
+    fn range_of_time<R, T>(&self, range: R) -> Range<usize>
+    where
+        E: Timestamped<Time = T>,
+        T: Ord,
+        R: RangeBounds<T>,
+    {
+        self.as_slice().range_of_time(range)
+    }
+
+    fn range_of_time_by_key<R, K, F>(&self, range: R, f: F) -> Range<usize>
+    where
+        F: FnMut(&E) -> K,
+        K: Ord,
+        R: RangeBounds<K>,
+    {
+        self.as_slice().range_of_time_by_key(range, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::{TimeRange, Timestamped};
+    use std::time::{Duration, SystemTime};
+
+    struct Event {
+        at: SystemTime,
+        _name: &'static str,
+    }
+
+    impl Timestamped for Event {
+        type Time = SystemTime;
+        fn timestamp(&self) -> SystemTime {
+            self.at
+        }
+    }
+
+    #[test]
+    fn test_range_of_time() {
+        let t0 = SystemTime::UNIX_EPOCH;
+        let at = |s| t0 + Duration::from_secs(s);
+        let v = [1, 2, 2, 4, 7]
+            .into_iter()
+            .map(|s| Event { at: at(s), _name: "e" })
+            .collect::<Vec<_>>();
+        assert_eq!(v.range_of_time(at(2)..at(4)), 1..3);
+        assert_eq!(v.range_of_time(at(2)..=at(4)), 1..4);
+        assert_eq!(v.range_of_time(at(5)..at(6)), 4..4);
+        assert_eq!(v.range_of_time(at(8)..), 5..5);
+        assert_eq!(v.range_of_time(..at(1)), 0..0);
+        assert_eq!(v.range_of_time(at(6)..at(3)), 4..4);
+
+        let offsets = vec![0u64, 10, 20, 30];
+        assert_eq!(offsets.range_of_time_by_key(5..=20, |&o| o), 1..3);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_range_of_time_chrono() {
+        use chrono::{TimeZone, Utc};
+        let at = |s| Utc.timestamp_opt(s, 0).unwrap();
+        let v = vec![at(10), at(20), at(30)];
+        assert_eq!(v.range_of_time(at(15)..at(30)), 1..2);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_range_of_time_time() {
+        use time::OffsetDateTime;
+        let at = |s| OffsetDateTime::from_unix_timestamp(s).unwrap();
+        let v = vec![at(10), at(20), at(30)];
+        assert_eq!(v.range_of_time(at(20)..), 1..3);
+    }
+}
@@ -0,0 +1,36 @@
+//! Optional `tracing` instrumentation. Without the `tracing` feature the macros only
+//! borrow their field values, so call sites need no `cfg` of their own.
+
+#[cfg(feature = "tracing")]
+pub(crate) type SpanGuard = tracing::span::EnteredSpan;
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct SpanGuard;
+
+/// Enters a debug span until the returned guard is dropped.
+macro_rules! debug_span {
+    ($name:literal $(, $k:ident = $v:expr)* $(,)?) => {{
+        #[cfg(feature = "tracing")]
+        let guard: $crate::trace::SpanGuard = ::tracing::debug_span!($name $(, $k = $v)*).entered();
+        #[cfg(not(feature = "tracing"))]
+        let guard = {
+            $(let _ = &$v;)*
+            $crate::trace::SpanGuard
+        };
+        guard
+    }};
+}
+
+/// Emits a debug event with the given fields.
+macro_rules! debug_event {
+    ($msg:literal $(, $k:ident = $v:expr)* $(,)?) => {{
+        #[cfg(feature = "tracing")]
+        ::tracing::debug!($($k = $v,)* $msg);
+        #[cfg(not(feature = "tracing"))]
+        {
+            $(let _ = &$v;)*
+        }
+    }};
+}
+
+pub(crate) use {debug_event, debug_span};
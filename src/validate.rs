@@ -0,0 +1,177 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+/// An adjacent pair out of order: `prev`, at `index - 1`, compares greater than `next`, at `index`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Violation<'a, T> {
+    pub index: usize,
+    pub prev: &'a T,
+    pub next: &'a T,
+}
+
+/// The result of a full sortedness scan, returned by the [`ValidateSorted`] methods.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortednessReport<'a, T> {
+    /// Number of elements scanned.
+    pub len: usize,
+    /// Every out-of-order adjacent pair, by index.
+    pub violations: Vec<Violation<'a, T>>,
+    /// Adjacent pairs that compare equal.
+    pub duplicate_pairs: usize,
+    /// Maximal runs of two or more equal adjacent elements.
+    pub duplicate_runs: usize,
+    /// Length of the longest run of equal adjacent elements, `1` if there are none.
+    pub longest_run: usize,
+}
+
+impl<T> SortednessReport<'_, T> {
+    /// Non-strict order: no element is greater than the next.
+    pub fn is_sorted(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Strict order: each element is less than the next.
+    pub fn is_strictly_sorted(&self) -> bool {
+        self.is_sorted() && self.duplicate_pairs == 0
+    }
+
+    pub fn first_violation(&self) -> Option<&Violation<'_, T>> {
+        self.violations.first()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Display for SortednessReport<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let order = match (self.is_strictly_sorted(), self.is_sorted()) {
+            (true, _) => "strictly sorted",
+            (false, true) => "sorted",
+            (false, false) => "not sorted",
+        };
+        write!(f, "{} elements, {order}", self.len)?;
+        if self.duplicate_runs > 0 {
+            write!(
+                f,
+                ", {} duplicate runs (longest {})",
+                self.duplicate_runs, self.longest_run
+            )?;
+        }
+        for v in &self.violations {
+            write!(f, "\n[{}] {:?} > [{}] {:?}", v.index - 1, v.prev, v.index, v.next)?;
+        }
+        Ok(())
+    }
+}
+
+/// A full scan for ingesting data that claims to be sorted: reports every adjacent pair out
+/// of order along with duplicate statistics, instead of a bare `bool`.
+/// ```
+/// # use bound_stl::ValidateSorted;
+/// let v = vec![1, 2, 2, 5, 4, 4, 4, 9];
+/// let report = v.validate_sorted();
+/// assert!(!report.is_sorted());
+/// assert_eq!(report.violations.len(), 1);
+/// assert_eq!((report.violations[0].index, *report.violations[0].prev), (4, 5));
+/// assert_eq!((report.duplicate_runs, report.longest_run), (2, 3));
+///
+/// let rows = [("ann", 3), ("bob", 1)];
+/// assert!(rows.validate_sorted_by_key(|r| r.0).is_strictly_sorted());
+/// ```
+pub trait ValidateSorted {
+    type Item;
+
+    fn validate_sorted(&self) -> SortednessReport<'_, Self::Item>;
+
+    fn validate_sorted_by<F>(&self, cmp: F) -> SortednessReport<'_, Self::Item>
+    where
+        F: FnMut(&Self::Item, &Self::Item) -> Ordering;
+
+    fn validate_sorted_by_key<K, F>(&self, f: F) -> SortednessReport<'_, Self::Item>
+    where
+        F: FnMut(&Self::Item) -> K,
+        K: Ord;
+}
+
+impl<T: Ord> ValidateSorted for [T] {
+    type Item = T;
+
+    fn validate_sorted(&self) -> SortednessReport<'_, T> {
+        self.validate_sorted_by(|a, b| a.cmp(b))
+    }
+
+    fn validate_sorted_by<F>(&self, mut cmp: F) -> SortednessReport<'_, T>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let mut report = SortednessReport {
+            len: self.len(),
+            violations: Vec::new(),
+            duplicate_pairs: 0,
+            duplicate_runs: 0,
+            longest_run: self.len().min(1),
+        };
+        let mut run = 1;
+        for (i, w) in self.windows(2).enumerate() {
+            match cmp(&w[0], &w[1]) {
+                Ordering::Equal => {
+                    report.duplicate_pairs += 1;
+                    run += 1;
+                    report.duplicate_runs += (run == 2) as usize;
+                    report.longest_run = report.longest_run.max(run);
+                    continue;
+                }
+                Ordering::Greater => report.violations.push(Violation {
+                    index: i + 1,
+                    prev: &w[0],
+                    next: &w[1],
+                }),
+                Ordering::Less => {}
+            }
+            run = 1;
+        }
+        report
+    }
+
+    fn validate_sorted_by_key<K, F>(&self, mut f: F) -> SortednessReport<'_, T>
+    where
+        F: FnMut(&T) -> K,
+        K: Ord,
+    {
+        self.validate_sorted_by(|a, b| f(a).cmp(&f(b)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::ValidateSorted;
+
+    #[test]
+    fn test_validate_sorted() {
+        let v = [3, 3, 1, 2, 2, 2, 2, 0, 5];
+        let report = v.validate_sorted();
+        let at = report
+            .violations
+            .iter()
+            .map(|x| (x.index, *x.prev, *x.next))
+            .collect::<Vec<_>>();
+        assert_eq!(at, [(2, 3, 1), (7, 2, 0)]);
+        assert_eq!(report.duplicate_pairs, 4);
+        assert_eq!(report.duplicate_runs, 2);
+        assert_eq!(report.longest_run, 4);
+        assert_eq!(
+            report.to_string(),
+            "9 elements, not sorted, 2 duplicate runs (longest 4)\n[1] 3 > [2] 1\n[6] 2 > [7] 0"
+        );
+
+        let desc = v.validate_sorted_by(|a, b| b.cmp(a));
+        assert_eq!(desc.violations.len(), 2);
+
+        let sorted = [1, 2, 2, 3];
+        assert!(sorted.validate_sorted().is_sorted());
+        assert!(!sorted.validate_sorted().is_strictly_sorted());
+        let empty: [i32; 0] = [];
+        let report = empty.validate_sorted();
+        assert!(report.is_strictly_sorted());
+        assert_eq!((report.len, report.longest_run), (0, 0));
+        assert_eq!(report.to_string(), "0 elements, strictly sorted");
+    }
+}
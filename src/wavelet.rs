@@ -0,0 +1,198 @@
+use crate::bits::RankBits;
+use std::ops::Range;
+
+/// A static wavelet tree over an integer sequence, answering positional rank queries in
+/// `O(log σ)` where σ is the largest value.
+///
+/// It is laid out as a wavelet matrix: one bit vector per bit of the values, from the most
+/// significant down, each followed by a stable partition of the sequence on that bit.
+/// Ranges are positions in the original sequence and, like slice ranges, panic when out of bounds.
+/// ```
+/// # use bound_stl::WaveletTree;
+/// let wt = WaveletTree::new(&[5, 1, 4, 1, 3, 9, 2, 6]);
+/// assert_eq!(wt.rank(1, 0..4), 2);               // two 1s among the first four
+/// assert_eq!(wt.kth_smallest(2..7, 0), Some(1)); // min of [4, 1, 3, 9, 2]
+/// assert_eq!(wt.kth_smallest(2..7, 2), Some(3)); // median of the same
+/// assert_eq!(wt.count_in_range(0..8, 2..6), 4);  // 5, 4, 3 and 2
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct WaveletTree {
+    len: usize,
+    /// `levels[l]` holds bit `bits - 1 - l` of the sequence as permuted at level `l`
+    levels: Vec<RankBits>,
+    zeros: Vec<usize>,
+}
+
+impl WaveletTree {
+    pub fn new(values: &[u64]) -> Self {
+        let max = values.iter().copied().max().unwrap_or(0);
+        let bits = (u64::BITS - max.leading_zeros()) as usize;
+        let mut cur = values.to_vec();
+        let mut levels = Vec::with_capacity(bits);
+        let mut zeros = Vec::with_capacity(bits);
+        for l in 0..bits {
+            let shift = bits - 1 - l;
+            levels.push(RankBits::new(cur.iter().map(|v| v >> shift & 1 == 1)));
+            let (mut lo, hi): (Vec<u64>, Vec<u64>) = cur.iter().partition(|&&v| v >> shift & 1 == 0);
+            zeros.push(lo.len());
+            lo.extend(hi);
+            cur = lo;
+        }
+        WaveletTree {
+            len: values.len(),
+            levels,
+            zeros,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The value at position `i`.
+    pub fn get(&self, mut i: usize) -> u64 {
+        assert!(i < self.len, "index {i} out of bounds for length {}", self.len);
+        let mut v = 0;
+        for (level, &z) in self.levels.iter().zip(&self.zeros) {
+            v <<= 1;
+            if level.get(i) {
+                v |= 1;
+                i = z + level.rank1(i);
+            } else {
+                i = level.rank0(i);
+            }
+        }
+        v
+    }
+
+    /// Number of occurrences of `x` in `range`.
+    pub fn rank(&self, x: u64, range: Range<usize>) -> usize {
+        let (mut l, mut r) = self.check(range);
+        if self.too_wide(x) {
+            return 0;
+        }
+        for (d, (level, &z)) in self.levels.iter().zip(&self.zeros).enumerate() {
+            if self.bit(x, d) {
+                (l, r) = (z + level.rank1(l), z + level.rank1(r));
+            } else {
+                (l, r) = (level.rank0(l), level.rank0(r));
+            }
+        }
+        r - l
+    }
+
+    /// The `k`-th smallest value in `range`, counting from 0; `None` if the range has no more
+    /// than `k` elements.
+    pub fn kth_smallest(&self, range: Range<usize>, mut k: usize) -> Option<u64> {
+        let (mut l, mut r) = self.check(range);
+        if k >= r - l {
+            return None;
+        }
+        let mut v = 0;
+        for (level, &z) in self.levels.iter().zip(&self.zeros) {
+            let (l0, r0) = (level.rank0(l), level.rank0(r));
+            v <<= 1;
+            if k < r0 - l0 {
+                (l, r) = (l0, r0);
+            } else {
+                k -= r0 - l0;
+                v |= 1;
+                (l, r) = (z + (l - l0), z + (r - r0));
+            }
+        }
+        Some(v)
+    }
+
+    /// Number of elements in `range` whose value lies in `values`.
+    pub fn count_in_range(&self, range: Range<usize>, values: Range<u64>) -> usize {
+        if values.start >= values.end {
+            return 0;
+        }
+        self.count_less(range.clone(), values.end) - self.count_less(range, values.start)
+    }
+
+    /// Number of elements in `range` less than `x`.
+    pub fn count_less(&self, range: Range<usize>, x: u64) -> usize {
+        let (mut l, mut r) = self.check(range);
+        if self.too_wide(x) {
+            return r - l;
+        }
+        let mut count = 0;
+        for (d, (level, &z)) in self.levels.iter().zip(&self.zeros).enumerate() {
+            let (l0, r0) = (level.rank0(l), level.rank0(r));
+            if self.bit(x, d) {
+                count += r0 - l0;
+                (l, r) = (z + (l - l0), z + (r - r0));
+            } else {
+                (l, r) = (l0, r0);
+            }
+        }
+        count
+    }
+
+    fn check(&self, range: Range<usize>) -> (usize, usize) {
+        assert!(
+            range.start <= range.end && range.end <= self.len,
+            "range {range:?} out of bounds for length {}",
+            self.len
+        );
+        (range.start, range.end)
+    }
+
+    /// Whether `x` has bits above the widest stored value.
+    fn too_wide(&self, x: u64) -> bool {
+        self.levels.len() < 64 && x >> self.levels.len() != 0
+    }
+
+    /// Bit of `x` examined at depth `d`.
+    fn bit(&self, x: u64, d: usize) -> bool {
+        x >> (self.levels.len() - 1 - d) & 1 == 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::WaveletTree;
+
+    #[test]
+    fn test_wavelet_tree() {
+        let mut seed = 12345u64;
+        let values = (0..300)
+            .map(|_| {
+                seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                (seed >> 33) % 50
+            })
+            .collect::<Vec<_>>();
+        let wt = WaveletTree::new(&values);
+        assert!(values.iter().enumerate().all(|(i, &v)| wt.get(i) == v));
+        for (l, r) in [(0, 300), (10, 20), (57, 58), (100, 100), (0, 1), (150, 299)] {
+            let window = &values[l..r];
+            let mut sorted = window.to_vec();
+            sorted.sort();
+            for k in 0..=window.len() {
+                assert_eq!(wt.kth_smallest(l..r, k), sorted.get(k).copied());
+            }
+            for x in [0, 7, 25, 49, 50, 1000] {
+                assert_eq!(wt.rank(x, l..r), window.iter().filter(|&&v| v == x).count());
+                assert_eq!(wt.count_less(l..r, x), window.iter().filter(|&&v| v < x).count());
+            }
+            assert_eq!(
+                wt.count_in_range(l..r, 10..20),
+                window.iter().filter(|&&v| (10..20).contains(&v)).count()
+            );
+        }
+
+        let zeros = WaveletTree::new(&[0, 0, 0]);
+        assert_eq!(zeros.rank(0, 0..3), 3);
+        assert_eq!(zeros.rank(1, 0..3), 0);
+        assert_eq!(zeros.kth_smallest(1..3, 1), Some(0));
+        let wide = WaveletTree::new(&[u64::MAX, 0]);
+        assert_eq!(wide.count_less(0..2, u64::MAX), 1);
+        assert_eq!(wide.kth_smallest(0..2, 1), Some(u64::MAX));
+        assert!(WaveletTree::new(&[]).is_empty());
+    }
+}
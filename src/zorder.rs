@@ -0,0 +1,209 @@
+use crate::LowerBound;
+
+/// Interleaves the bits of a point into its Morton code: bit `k` of coordinate `d` becomes
+/// bit `k * D + d` of the code. Supports up to four dimensions.
+pub fn morton_encode<const D: usize>(point: [u32; D]) -> u128 {
+    assert!((1..=4).contains(&D), "Morton codes support 1 to 4 dimensions");
+    let mut code = 0;
+    for (d, &c) in point.iter().enumerate() {
+        for k in 0..32 {
+            code |= ((c >> k & 1) as u128) << (k * D + d);
+        }
+    }
+    code
+}
+
+/// The inverse of [`morton_encode`].
+pub fn morton_decode<const D: usize>(code: u128) -> [u32; D] {
+    assert!((1..=4).contains(&D), "Morton codes support 1 to 4 dimensions");
+    let mut point = [0; D];
+    for (d, c) in point.iter_mut().enumerate() {
+        for k in 0..32 {
+            *c |= ((code >> (k * D + d) & 1) as u32) << k;
+        }
+    }
+    point
+}
+
+/// Points in `D` dimensions sorted by Morton (Z-order) code, with box queries.
+///
+/// A box `[min, max]` spans the codes from `encode(min)` to `encode(max)`, but that interval
+/// also covers points outside the box. A query walks it with lower bounds over the sorted
+/// codes and, on reaching a code outside the box, computes BIGMIN, the smallest code after it
+/// that lies inside again, and jumps there, so the Z-order intervals of the box are searched
+/// one by one without enumerating them up front.
+/// ```
+/// # use bound_stl::ZOrderIndex;
+/// let shops = ZOrderIndex::new([([2, 3], "bakery"), ([5, 1], "florist"), ([6, 6], "cafe"), ([1, 7], "bar")]);
+/// let mut found = shops.query([1, 1], [5, 4]).map(|(_, name)| *name).collect::<Vec<_>>();
+/// found.sort();
+/// assert_eq!(found, ["bakery", "florist"]);
+/// assert_eq!(shops.count([0, 5], [7, 7]), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ZOrderIndex<V, const D: usize> {
+    codes: Vec<u128>,
+    items: Vec<([u32; D], V)>,
+}
+
+impl<V, const D: usize> Default for ZOrderIndex<V, D> {
+    fn default() -> Self {
+        ZOrderIndex {
+            codes: Vec::new(),
+            items: Vec::new(),
+        }
+    }
+}
+
+impl<V, const D: usize> ZOrderIndex<V, D> {
+    pub fn new<I: IntoIterator<Item = ([u32; D], V)>>(points: I) -> Self {
+        let mut entries = points
+            .into_iter()
+            .map(|(p, v)| (morton_encode(p), (p, v)))
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|&(code, _)| code);
+        let (codes, items) = entries.into_iter().unzip();
+        ZOrderIndex { codes, items }
+    }
+
+    pub fn len(&self) -> usize {
+        self.codes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.codes.is_empty()
+    }
+
+    /// Adds a point after the points with the same coordinates.
+    pub fn insert(&mut self, point: [u32; D], value: V) {
+        let code = morton_encode(point);
+        let pos = self.codes.partition_point(|&c| c <= code);
+        self.codes.insert(pos, code);
+        self.items.insert(pos, (point, value));
+    }
+
+    /// All points, in Z-order.
+    pub fn iter(&self) -> std::slice::Iter<'_, ([u32; D], V)> {
+        self.items.iter()
+    }
+
+    /// The points inside the box `[min, max]`, bounds included, in Z-order.
+    pub fn query(&self, min: [u32; D], max: [u32; D]) -> impl Iterator<Item = &([u32; D], V)> {
+        self.query_positions(min, max).map(|i| &self.items[i])
+    }
+
+    pub fn count(&self, min: [u32; D], max: [u32; D]) -> usize {
+        self.query_positions(min, max).count()
+    }
+
+    fn query_positions(&self, min: [u32; D], max: [u32; D]) -> impl Iterator<Item = usize> + '_ {
+        let empty = min.iter().zip(&max).any(|(lo, hi)| lo > hi);
+        let (zmin, zmax) = (morton_encode(min), morton_encode(max));
+        let mut pos = if empty {
+            self.len()
+        } else {
+            self.codes.lower_bound(&zmin).unwrap_or_else(|e| e)
+        };
+        std::iter::from_fn(move || {
+            while pos < self.len() && self.codes[pos] <= zmax {
+                let code = self.codes[pos];
+                if in_box::<D>(code, zmin, zmax) {
+                    pos += 1;
+                    return Some(pos - 1);
+                }
+                let next = bigmin::<D>(code, zmin, zmax);
+                pos += self.codes[pos..].lower_bound(&next).unwrap_or_else(|e| e);
+            }
+            None
+        })
+    }
+}
+
+impl<V, const D: usize> FromIterator<([u32; D], V)> for ZOrderIndex<V, D> {
+    fn from_iter<I: IntoIterator<Item = ([u32; D], V)>>(iter: I) -> Self {
+        ZOrderIndex::new(iter)
+    }
+}
+
+/// The code bits belonging to dimension `d`.
+fn dim_mask<const D: usize>(d: usize) -> u128 {
+    (0..32).fold(0, |m, k| m | 1 << (k * D + d))
+}
+
+fn in_box<const D: usize>(code: u128, zmin: u128, zmax: u128) -> bool {
+    (0..D).all(|d| {
+        let m = dim_mask::<D>(d);
+        (zmin & m) <= (code & m) && (code & m) <= (zmax & m)
+    })
+}
+
+/// The smallest code greater than `code` inside the box spanned by `zmin` and `zmax`,
+/// for a `code` between them but outside the box (Tropf and Herzog's BIGMIN).
+fn bigmin<const D: usize>(code: u128, mut zmin: u128, mut zmax: u128) -> u128 {
+    let mut bigmin = zmin;
+    for b in (0..32 * D).rev() {
+        let bit = 1u128 << b;
+        // the lower bits of the same dimension as bit `b`
+        let below = dim_mask::<D>(b % D) & (bit - 1);
+        match (code & bit != 0, zmin & bit != 0, zmax & bit != 0) {
+            (false, false, true) => {
+                bigmin = (zmin & !below) | bit;
+                zmax = (zmax & !bit) | below;
+            }
+            (false, true, true) => return zmin,
+            (true, false, false) => return bigmin,
+            (true, false, true) => zmin = (zmin & !below) | bit,
+            _ => {}
+        }
+    }
+    bigmin
+}
+
+#[cfg(test)]
+mod tests {
+    use bound_stl::{morton_decode, morton_encode, ZOrderIndex};
+
+    #[test]
+    fn test_zorder_index() {
+        assert_eq!(morton_encode([0b11, 0b01]), 0b0111);
+        assert_eq!(
+            morton_decode::<3>(morton_encode([7, 123_456, u32::MAX])),
+            [7, 123_456, u32::MAX]
+        );
+
+        let mut seed = 3u32;
+        let mut next = move || {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            (seed >> 16) % 40
+        };
+        let points = (0..400).map(|i| ([next(), next(), next()], i)).collect::<Vec<_>>();
+        let index = points.iter().copied().collect::<ZOrderIndex<_, 3>>();
+        assert_eq!(index.len(), 400);
+        for _ in 0..50 {
+            let (a, b) = ([next(), next(), next()], [next(), next(), next()]);
+            let (min, max) = (
+                std::array::from_fn(|d| a[d].min(b[d])),
+                std::array::from_fn(|d| a[d].max(b[d])),
+            );
+            let inside = |p: &[u32; 3]| (0..3).all(|d| min[d] <= p[d] && p[d] <= max[d]);
+            let mut expect = points
+                .iter()
+                .filter(|(p, _)| inside(p))
+                .map(|&(_, i)| i)
+                .collect::<Vec<_>>();
+            let mut found = index.query(min, max).map(|&(_, i)| i).collect::<Vec<_>>();
+            expect.sort();
+            found.sort();
+            assert_eq!(found, expect, "box {min:?} - {max:?}");
+        }
+        assert_eq!(index.count([5, 5, 5], [4, 9, 9]), 0);
+
+        let mut grid = ZOrderIndex::default();
+        for x in 0..8 {
+            for y in 0..8 {
+                grid.insert([x, y], ());
+            }
+        }
+        assert_eq!(grid.count([1, 2], [6, 3]), 12);
+    }
+}